@@ -0,0 +1,136 @@
+use std::fmt::{Display, Formatter};
+
+use crate::{
+    column::split_outside_quotes,
+    schema::is_null,
+    value::{parse_integer, parse_number},
+    CSVError, Cell, ColumnSelector, ColumnType,
+};
+
+/// A function summarising a column within each group of rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggregateFunction {
+    /// The number of rows, or of non-null entries when given a column.
+    Count,
+    /// The total of a numeric column.
+    Sum,
+    /// The mean of a numeric column.
+    Avg,
+    /// The smallest entry, compared according to the column type.
+    Min,
+    /// The largest entry, compared according to the column type.
+    Max,
+}
+
+/// An aggregate written as `function(column)`, such as `sum(sales)` or `count(*)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Aggregate {
+    pub function: AggregateFunction,
+    /// The column to summarise. `None` stands for `*`, which only `count` accepts.
+    pub column: Option<ColumnSelector>,
+}
+
+impl Aggregate {
+    /// Parses a comma-separated list of aggregates, such as `sum(sales), avg(price), count(*)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CSVError::InvalidAggregate`] if an aggregate is malformed or uses an unknown
+    /// function.
+    ///
+    pub fn parse_list(spec: &str) -> Result<Vec<Aggregate>, CSVError> {
+        split_outside_quotes(spec, |ch| ch == ',')
+            .into_iter()
+            .map(Aggregate::parse)
+            .collect()
+    }
+
+    fn parse(spec: &str) -> Result<Aggregate, CSVError> {
+        let invalid = || CSVError::InvalidAggregate(spec.to_owned());
+        let (name, argument) = spec.split_once('(').ok_or_else(invalid)?;
+        let argument = argument
+            .trim_end()
+            .strip_suffix(')')
+            .ok_or_else(invalid)?
+            .trim();
+        let function = match name.trim().to_ascii_lowercase().as_str() {
+            "count" => AggregateFunction::Count,
+            "sum" => AggregateFunction::Sum,
+            "avg" | "mean" => AggregateFunction::Avg,
+            "min" => AggregateFunction::Min,
+            "max" => AggregateFunction::Max,
+            _ => return Err(invalid()),
+        };
+        let column = match argument {
+            "" => return Err(invalid()),
+            "*" if function == AggregateFunction::Count => None,
+            "*" => return Err(invalid()),
+            column => Some(ColumnSelector::parse(column)),
+        };
+        Ok(Aggregate { function, column })
+    }
+}
+
+impl AggregateFunction {
+    /// Returns whether the function can summarise a column of the given type.
+    pub(crate) fn accepts(&self, column_type: ColumnType) -> bool {
+        match self {
+            AggregateFunction::Sum | AggregateFunction::Avg => column_type.is_numeric(),
+            AggregateFunction::Count | AggregateFunction::Min | AggregateFunction::Max => true,
+        }
+    }
+
+    /// Applies the function to the entries of one group, ignoring nulls. Returns `_` when a
+    /// sum, mean, minimum or maximum has no entries to work with.
+    pub(crate) fn apply(&self, column_type: ColumnType, entries: &[&Cell]) -> Cell {
+        let values: Vec<&str> = entries
+            .iter()
+            .filter(|cell| !is_null(cell))
+            .map(|cell| cell.as_str())
+            .collect();
+        let result = match self {
+            AggregateFunction::Count => values.len().to_string(),
+            _ if values.is_empty() => return Cell::Null,
+            AggregateFunction::Sum if column_type == ColumnType::Integer => values
+                .iter()
+                .try_fold(0i64, |total, value| {
+                    total.checked_add(parse_integer(value)?)
+                })
+                .map(|total| total.to_string())
+                .unwrap_or_else(|| sum(&values).to_string()),
+            AggregateFunction::Sum => sum(&values).to_string(),
+            AggregateFunction::Avg => (sum(&values) / values.len() as f64).to_string(),
+            AggregateFunction::Min | AggregateFunction::Max => {
+                let compare = |left: &&str, right: &&str| {
+                    column_type
+                        .compare(left, right)
+                        .unwrap_or_else(|| left.cmp(right))
+                };
+                let extreme = if *self == AggregateFunction::Min {
+                    values.into_iter().min_by(compare)
+                } else {
+                    values.into_iter().max_by(compare)
+                };
+                extreme.map(|value| value.to_string()).unwrap_or_default()
+            }
+        };
+        Cell::Value(result)
+    }
+}
+
+impl Display for AggregateFunction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            AggregateFunction::Count => "count",
+            AggregateFunction::Sum => "sum",
+            AggregateFunction::Avg => "avg",
+            AggregateFunction::Min => "min",
+            AggregateFunction::Max => "max",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+fn sum(values: &[&str]) -> f64 {
+    values.iter().filter_map(|value| parse_number(value)).sum()
+}
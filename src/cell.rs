@@ -0,0 +1,81 @@
+use std::{
+    cmp::Ordering,
+    fmt::{Display, Formatter},
+};
+
+/// A single entry of a CSV file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub enum Cell {
+    /// Lack of entry, written as `_` in the file.
+    #[default]
+    Null,
+    /// Any other entry, stored verbatim.
+    Value(String),
+}
+
+impl Cell {
+    /// The text representing a [`Cell::Null`] in a file.
+    pub const NULL_MARKER: &'static str = "_";
+
+    /// Creates a cell from its textual form, turning `_` into [`Cell::Null`].
+    pub fn parse(raw: &str) -> Self {
+        if raw == Cell::NULL_MARKER {
+            Cell::Null
+        } else {
+            Cell::Value(raw.to_owned())
+        }
+    }
+
+    /// Returns the textual form of the cell, as written to the file.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Cell::Null => Cell::NULL_MARKER,
+            Cell::Value(value) => value,
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Cell::Null)
+    }
+}
+
+impl AsRef<str> for Cell {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<&str> for Cell {
+    fn from(value: &str) -> Self {
+        Cell::parse(value)
+    }
+}
+
+impl From<String> for Cell {
+    fn from(value: String) -> Self {
+        if value == Cell::NULL_MARKER {
+            Cell::Null
+        } else {
+            Cell::Value(value)
+        }
+    }
+}
+
+impl Display for Cell {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Cells are ordered by their textual form.
+impl Ord for Cell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl PartialOrd for Cell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
@@ -0,0 +1,268 @@
+use csvr::{Backup, CSVError, Dialect, MergeOptions, StatusOutput, TextEncoding};
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum FileLoadError {
+    FileNameMissing,
+    ExtraArguments,
+    UnknownOption(String),
+    MissingOptionValue(String),
+    InvalidCharacter(String),
+    InvalidBackup(String),
+    UnknownEncoding(String),
+    StdinWithoutCommands,
+    NoMatchingFiles(String),
+    Merge(String, String),
+    Load(CSVError),
+}
+
+impl fmt::Display for FileLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileLoadError::FileNameMissing => write!(f, "csvr: missing argument: filename"),
+            FileLoadError::ExtraArguments => write!(f, "csvr: more arguments than required"),
+            FileLoadError::UnknownOption(option) => write!(f, "csvr: unknown option: {}", option),
+            FileLoadError::MissingOptionValue(option) => {
+                write!(f, "csvr: missing value for option: {}", option)
+            }
+            FileLoadError::InvalidCharacter(value) => write!(
+                f,
+                "csvr: invalid character: '{}': use a single character or one of 'comma', 'tab', 'semicolon', 'pipe', 'space'",
+                value
+            ),
+            FileLoadError::InvalidBackup(value) => write!(
+                f,
+                "csvr: invalid backup: '{}': use one of 'bak', 'timestamp', 'none'",
+                value
+            ),
+            FileLoadError::UnknownEncoding(value) => write!(
+                f,
+                "csvr: unknown encoding: '{}': use one of 'utf-8', 'utf-16le', 'utf-16be', 'latin1', 'windows-1252'",
+                value
+            ),
+            FileLoadError::StdinWithoutCommands => write!(
+                f,
+                "csvr: reading the file from stdin requires commands given with -c or --script"
+            ),
+            FileLoadError::NoMatchingFiles(pattern) => {
+                write!(f, "csvr: no files match: {}", pattern)
+            }
+            FileLoadError::Merge(file_name, err) => {
+                write!(f, "csvr: error appending file {}: {}", file_name, err)
+            }
+            FileLoadError::Load(err) => write!(f, "csvr: error loading file: {}", err),
+        }
+    }
+}
+
+/// Options supplied on the command line.
+#[derive(Debug, Default, PartialEq)]
+pub struct CliOptions {
+    pub file_name: String,
+    pub delimiter: Option<char>,
+    pub quote: Option<char>,
+    pub escape: Option<char>,
+    pub no_sniff: bool,
+    /// Whether the first line of the file is data rather than a header.
+    pub no_header: bool,
+    /// Whether to write the generated titles of a file loaded with `--no-header`.
+    pub write_header: bool,
+    /// Encoding to read the file in instead of detecting it.
+    pub encoding: Option<TextEncoding>,
+    /// Encoding to write the file in instead of the one it was read in.
+    pub write_encoding: Option<TextEncoding>,
+    /// Commands given with `-c`, run in order instead of starting the interactive prompt.
+    pub commands: Vec<String>,
+    /// File holding commands to run, one per line, instead of starting the interactive prompt.
+    pub script: Option<String>,
+    /// Where `write` without a file name saves the file instead of the loaded path. `-` is
+    /// standard output.
+    pub output: Option<String>,
+    /// Whether to hide status messages.
+    pub quiet: bool,
+    /// Whether `write` keeps the previous version of the file it replaces.
+    pub backup: Backup,
+    /// Whether to write `LF` line endings, no byte order mark and a final line break instead of
+    /// the ones the file was loaded with.
+    pub normalize: bool,
+    /// Whether to append the rows of every file given to the first one.
+    pub concat: bool,
+    /// Files given after the first one with `--concat`.
+    pub concat_files: Vec<String>,
+    /// Whether `--concat` matches columns by title instead of by position.
+    pub by_name: bool,
+    /// Whether `--concat` adds the columns only later files have. Implies `by_name`.
+    pub add_columns: bool,
+    /// Title of the column recording which file each row came from with `--concat`.
+    pub source_column: Option<String>,
+}
+
+impl CliOptions {
+    /// Returns the dialect requested on the command line, or `None` if no dialect option was
+    /// supplied. Options that were not supplied fall back to the dialect implied by the file
+    /// extension.
+    pub fn dialect(&self) -> Option<Dialect> {
+        if self.delimiter.is_none() && self.quote.is_none() && self.escape.is_none() {
+            return None;
+        }
+        let mut dialect = Dialect::from_path(&self.file_name).unwrap_or_default();
+        if let Some(delimiter) = self.delimiter {
+            dialect.delimiter = delimiter;
+        }
+        if let Some(quote) = self.quote {
+            dialect.quote = quote;
+        }
+        if self.escape.is_some() {
+            dialect.escape = self.escape;
+        }
+        Some(dialect)
+    }
+
+    /// Returns how `--concat` merges files into the first one.
+    pub fn merge_options(&self) -> MergeOptions {
+        MergeOptions {
+            by_name: self.by_name || self.add_columns,
+            add_columns: self.add_columns,
+            source_column: self.source_column.clone(),
+        }
+    }
+
+    /// Returns `true` if commands were supplied on the command line, so no prompt is shown.
+    pub fn is_batch(&self) -> bool {
+        !self.commands.is_empty() || self.script.is_some()
+    }
+
+    /// Returns where status messages are printed. Without a prompt they go to standard error,
+    /// so that standard output only carries data.
+    pub fn status_output(&self) -> StatusOutput {
+        if self.quiet {
+            StatusOutput::Silent
+        } else if self.is_batch() {
+            StatusOutput::Stderr
+        } else {
+            StatusOutput::Stdout
+        }
+    }
+}
+
+/// Parses the process arguments (including the program name) into [`CliOptions`].
+///
+/// Usage: `csvr <file|-> [--delimiter <char>] [--quote <char>] [--escape <char>] [--no-sniff]
+/// [--encoding <name>] [--write-encoding <name>] [--no-header [--write-header]]
+/// [-c <command>]... [--script <file>] [--output <file|->] [--backup <bak|timestamp>]
+/// [--normalize] [--quiet]`, or
+/// `csvr --concat <file>... [--by-name] [--add-columns] [--source-column <title>] ...`
+pub fn parse_args(args: Vec<String>) -> Result<CliOptions, FileLoadError> {
+    let mut options = CliOptions::default();
+    let mut file_name = None;
+    let mut args = args.into_iter().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--delimiter" | "-D" | "--quote" | "-Q" | "--escape" | "-E" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| FileLoadError::MissingOptionValue(arg.clone()))?;
+                let ch = parse_char(&value).ok_or(FileLoadError::InvalidCharacter(value))?;
+                match arg.as_str() {
+                    "--delimiter" | "-D" => options.delimiter = Some(ch),
+                    "--quote" | "-Q" => options.quote = Some(ch),
+                    _ => options.escape = Some(ch),
+                }
+            }
+            "--no-sniff" => options.no_sniff = true,
+            "--no-header" => options.no_header = true,
+            "--write-header" => options.write_header = true,
+            "--concat" => options.concat = true,
+            "--by-name" => options.by_name = true,
+            "--add-columns" => options.add_columns = true,
+            "--normalize" => options.normalize = true,
+            "--quiet" | "-q" => options.quiet = true,
+            "--encoding" | "--write-encoding" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| FileLoadError::MissingOptionValue(arg.clone()))?;
+                let encoding =
+                    TextEncoding::parse(&value).ok_or(FileLoadError::UnknownEncoding(value))?;
+                if arg == "--encoding" {
+                    options.encoding = Some(encoding);
+                } else {
+                    options.write_encoding = Some(encoding);
+                }
+            }
+            "--backup" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| FileLoadError::MissingOptionValue(arg.clone()))?;
+                options.backup =
+                    Backup::parse(&value).ok_or(FileLoadError::InvalidBackup(value))?;
+            }
+            "--command" | "-c" | "--script" | "--output" | "-o" | "--source-column" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| FileLoadError::MissingOptionValue(arg.clone()))?;
+                match arg.as_str() {
+                    "--script" => options.script = Some(value),
+                    "--output" | "-o" => options.output = Some(value),
+                    "--source-column" => options.source_column = Some(value),
+                    _ => options.commands.push(value),
+                }
+            }
+            _ if arg.starts_with('-') && arg.len() > 1 => {
+                return Err(FileLoadError::UnknownOption(arg));
+            }
+            _ => {
+                if file_name.is_none() {
+                    file_name = Some(arg);
+                } else {
+                    options.concat_files.push(arg);
+                }
+            }
+        }
+    }
+
+    options.file_name = file_name.ok_or(FileLoadError::FileNameMissing)?;
+    if !options.concat_files.is_empty() && !options.concat {
+        return Err(FileLoadError::ExtraArguments);
+    }
+    if options.file_name == "-" && !options.is_batch() {
+        return Err(FileLoadError::StdinWithoutCommands);
+    }
+    Ok(options)
+}
+
+/// Expands a glob pattern such as `part-*.csv` into the matching paths, in alphabetical order.
+/// Paths without glob characters are returned as they are.
+pub fn expand_paths(pattern: &str) -> Result<Vec<String>, FileLoadError> {
+    if !pattern.contains(['*', '?', '[']) {
+        return Ok(vec![pattern.to_owned()]);
+    }
+    let paths: Vec<String> = glob::glob(pattern)
+        .map_err(|err| FileLoadError::Load(CSVError::Io(err.to_string())))?
+        .filter_map(Result::ok)
+        .filter(|path| path.is_file())
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
+    if paths.is_empty() {
+        return Err(FileLoadError::NoMatchingFiles(pattern.to_owned()));
+    }
+    Ok(paths)
+}
+
+/// Parses a dialect character given either literally or by name.
+fn parse_char(value: &str) -> Option<char> {
+    match value {
+        "comma" => Some(','),
+        "tab" | "\\t" => Some('\t'),
+        "semicolon" => Some(';'),
+        "pipe" => Some('|'),
+        "space" => Some(' '),
+        _ => {
+            let mut chars = value.chars();
+            match (chars.next(), chars.next()) {
+                (Some(ch), None) => Some(ch),
+                _ => None,
+            }
+        }
+    }
+}
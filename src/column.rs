@@ -0,0 +1,92 @@
+use std::fmt::{Display, Formatter};
+
+/// Identifies a column either by its 1-based index or by its header title.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ColumnSelector {
+    Index(usize),
+    Name(String),
+}
+
+impl ColumnSelector {
+    /// Parses a column typed by the user. Whole numbers are indices and anything else is a
+    /// title. Quoted text, such as `"first name"` or `"2019"`, is always a title.
+    pub fn parse(text: &str) -> Self {
+        let text = text.trim();
+        for quote in ['"', '\''] {
+            if text.len() >= 2 && text.starts_with(quote) && text.ends_with(quote) {
+                let doubled = format!("{}{}", quote, quote);
+                return ColumnSelector::Name(
+                    text[1..text.len() - 1].replace(&doubled, &quote.to_string()),
+                );
+            }
+        }
+        match text.parse::<usize>() {
+            Ok(index) => ColumnSelector::Index(index),
+            Err(_) => ColumnSelector::Name(text.to_owned()),
+        }
+    }
+
+    /// Parses a comma-separated list of columns, such as `region, "unit price", 3`.
+    pub fn parse_list(text: &str) -> Vec<Self> {
+        split_outside_quotes(text, |ch| ch == ',')
+            .into_iter()
+            .map(ColumnSelector::parse)
+            .collect()
+    }
+}
+
+impl From<usize> for ColumnSelector {
+    fn from(index: usize) -> Self {
+        ColumnSelector::Index(index)
+    }
+}
+
+impl From<&str> for ColumnSelector {
+    fn from(name: &str) -> Self {
+        ColumnSelector::Name(name.to_owned())
+    }
+}
+
+impl From<String> for ColumnSelector {
+    fn from(name: String) -> Self {
+        ColumnSelector::Name(name)
+    }
+}
+
+impl From<&ColumnSelector> for ColumnSelector {
+    fn from(selector: &ColumnSelector) -> Self {
+        selector.clone()
+    }
+}
+
+impl Display for ColumnSelector {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ColumnSelector::Index(index) => write!(f, "#{}", index),
+            ColumnSelector::Name(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Splits `text` at the characters matching `is_separator` outside of quotes, dropping empty
+/// pieces and trimming the rest.
+pub(crate) fn split_outside_quotes(text: &str, is_separator: impl Fn(char) -> bool) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut quote = None;
+    for (index, ch) in text.char_indices() {
+        match quote {
+            Some(open) if ch == open => quote = None,
+            Some(_) => {}
+            None if ch == '"' || ch == '\'' => quote = Some(ch),
+            None if is_separator(ch) => {
+                pieces.push(text[start..index].trim());
+                start = index + ch.len_utf8();
+            }
+            None => {}
+        }
+    }
+    pieces.push(text[start..].trim());
+    pieces.retain(|piece| !piece.is_empty());
+    pieces
+}
@@ -0,0 +1,91 @@
+use std::{
+    fmt::{Debug, Formatter},
+    io::{self, Write},
+    sync::Arc,
+};
+
+/// Decides whether a mutation of a [`CSVFile`](crate::CSVFile) should go ahead.
+pub trait Confirm {
+    /// Returns `true` if the action described by `prompt` should be applied.
+    ///
+    /// `preview` prints the data affected by the action. Implementations that do not show
+    /// anything to a user are free to never call it.
+    fn confirm(&self, prompt: &str, preview: &dyn Fn()) -> bool;
+}
+
+/// Approves every action without asking. This is the default for a [`CSVFile`](crate::CSVFile).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AlwaysYes;
+
+impl Confirm for AlwaysYes {
+    fn confirm(&self, _prompt: &str, _preview: &dyn Fn()) -> bool {
+        true
+    }
+}
+
+/// Rejects every action without asking.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AlwaysNo;
+
+impl Confirm for AlwaysNo {
+    fn confirm(&self, _prompt: &str, _preview: &dyn Fn()) -> bool {
+        false
+    }
+}
+
+/// Prints the prompt and the affected data, then reads a `y/n` answer from stdin.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TerminalConfirm;
+
+impl Confirm for TerminalConfirm {
+    fn confirm(&self, prompt: &str, preview: &dyn Fn()) -> bool {
+        println!(">>>  \x1b[35m{}\x1b[0m", prompt);
+        preview();
+        print!(">>  \x1b[35m[y/n]:\x1b[0m ");
+        let _ = io::stdout().flush();
+        let mut input = String::new();
+        let _ = io::stdin().read_line(&mut input);
+        input.trim() == "y"
+    }
+}
+
+/// Delegates the decision to a closure receiving the prompt.
+#[derive(Clone, Copy)]
+pub struct CallbackConfirm<F>(pub F)
+where
+    F: Fn(&str) -> bool;
+
+impl<F> Confirm for CallbackConfirm<F>
+where
+    F: Fn(&str) -> bool,
+{
+    fn confirm(&self, prompt: &str, _preview: &dyn Fn()) -> bool {
+        (self.0)(prompt)
+    }
+}
+
+/// Shared handle to the [`Confirm`] implementation stored in a [`CSVFile`](crate::CSVFile).
+#[derive(Clone)]
+pub(crate) struct Confirmer(Arc<dyn Confirm + Send + Sync>);
+
+impl Confirmer {
+    pub(crate) fn new(confirm: impl Confirm + Send + Sync + 'static) -> Self {
+        Confirmer(Arc::new(confirm))
+    }
+
+    pub(crate) fn confirm(&self, prompt: &str, preview: &dyn Fn()) -> bool {
+        self.0.confirm(prompt, preview)
+    }
+}
+
+impl Default for Confirmer {
+    fn default() -> Self {
+        Confirmer::new(AlwaysYes)
+    }
+}
+
+impl Debug for Confirmer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Confirmer")
+    }
+}
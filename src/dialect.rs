@@ -0,0 +1,280 @@
+use std::{
+    fmt::{Display, Formatter},
+    path::Path,
+};
+
+/// Number of records inspected when sniffing a dialect.
+const SNIFF_RECORDS: usize = 20;
+
+/// Candidate delimiters in order of preference when sniffing.
+const SNIFF_DELIMITERS: [char; 5] = [',', '\t', ';', '|', ':'];
+
+/// Candidate quote characters in order of preference when sniffing.
+const SNIFF_QUOTES: [char; 2] = ['"', '\''];
+
+/// Character sequence separating records.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum LineTerminator {
+    #[default]
+    Lf,
+    CrLf,
+}
+
+impl LineTerminator {
+    /// Returns the terminator used by the first line break in `content`, defaulting to `Lf`.
+    pub fn detect(content: &str) -> Self {
+        match content.find('\n') {
+            Some(index) if content[..index].ends_with('\r') => LineTerminator::CrLf,
+            _ => LineTerminator::Lf,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineTerminator::Lf => "\n",
+            LineTerminator::CrLf => "\r\n",
+        }
+    }
+}
+
+impl Display for LineTerminator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LineTerminator::Lf => write!(f, "LF"),
+            LineTerminator::CrLf => write!(f, "CRLF"),
+        }
+    }
+}
+
+/// The byte order mark that may start a UTF-8 file.
+pub const BOM: char = '\u{feff}';
+
+/// How a file wraps its records, besides the line terminator of its [`Dialect`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Framing {
+    /// Whether the file starts with a UTF-8 byte order mark.
+    pub bom: bool,
+    /// Whether the last record is followed by a line terminator.
+    pub trailing_newline: bool,
+}
+
+impl Framing {
+    /// Returns the framing of `content`, the whole text of a file.
+    pub fn detect(content: &str) -> Self {
+        Framing {
+            bom: content.starts_with(BOM),
+            trailing_newline: content.ends_with('\n'),
+        }
+    }
+
+    /// Returns the framing most tools expect: no byte order mark and a line terminator after
+    /// the last record.
+    pub fn normalized() -> Self {
+        Framing {
+            bom: false,
+            trailing_newline: true,
+        }
+    }
+}
+
+/// Result of sniffing a file's contents.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Detection {
+    /// The dialect the file appears to be written in.
+    pub dialect: Dialect,
+    /// Whether the first record looks like a header.
+    pub has_header: bool,
+}
+
+/// Describes how fields and records are laid out in a delimited text file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dialect {
+    /// Character separating fields within a record.
+    pub delimiter: char,
+    /// Character used to wrap fields containing special characters.
+    pub quote: char,
+    /// Character that makes the following character literal. When `None`, quotes inside
+    /// quoted fields are escaped by doubling them as described in RFC 4180.
+    pub escape: Option<char>,
+    /// Sequence written between records.
+    pub line_terminator: LineTerminator,
+}
+
+impl Default for Dialect {
+    fn default() -> Self {
+        Dialect {
+            delimiter: ',',
+            quote: '"',
+            escape: None,
+            line_terminator: LineTerminator::Lf,
+        }
+    }
+}
+
+impl Dialect {
+    /// Returns a dialect with the given delimiter and the default quoting rules.
+    pub fn with_delimiter(delimiter: char) -> Self {
+        Dialect {
+            delimiter,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the conventional dialect for a file extension, or `None` if the extension
+    /// does not imply a delimiter.
+    ///
+    /// * `csv` - comma separated
+    /// * `tsv`, `tab` - tab separated
+    /// * `psv` - pipe separated
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "csv" => Some(Dialect::with_delimiter(',')),
+            "tsv" | "tab" => Some(Dialect::with_delimiter('\t')),
+            "psv" => Some(Dialect::with_delimiter('|')),
+            _ => None,
+        }
+    }
+
+    /// Returns the conventional dialect for the extension of `path`, if any.
+    pub fn from_path(path: &str) -> Option<Self> {
+        Path::new(path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(Dialect::from_extension)
+    }
+
+    /// Infers the dialect of `sample`, which should hold the beginning of a file.
+    ///
+    /// Every combination of candidate delimiter and quote character is used to parse the first
+    /// records of the sample. Only combinations splitting every record, the first one included,
+    /// into the same number of fields (at least two) are kept, and among them the one finding
+    /// more quoted fields, then more fields, wins. Returns `None` if no combination splits the
+    /// records into multiple fields consistently.
+    pub fn sniff(sample: &str) -> Option<Self> {
+        let line_terminator = LineTerminator::detect(sample);
+
+        let mut best: Option<(usize, usize, Dialect)> = None;
+        for quote in SNIFF_QUOTES {
+            for delimiter in SNIFF_DELIMITERS {
+                let mut dialect = Dialect {
+                    delimiter,
+                    quote,
+                    escape: None,
+                    line_terminator,
+                };
+                let escaped_quote = format!("\\{}", quote);
+                if sample.contains(&escaped_quote) {
+                    dialect.escape = Some('\\');
+                }
+
+                let records: Vec<String> = dialect
+                    .split_records(sample)
+                    .into_iter()
+                    .take(SNIFF_RECORDS)
+                    .collect();
+                if records.is_empty() {
+                    return None;
+                }
+                let quoted_fields = records
+                    .iter()
+                    .flat_map(|record| record.split(delimiter))
+                    .filter(|field| field.len() > 1 && field.starts_with(quote))
+                    .count();
+                let counts: Vec<usize> = records
+                    .iter()
+                    .map(|record| dialect.parse_record(record).len())
+                    .collect();
+                // Every record, the header included, must split into the same number of fields,
+                // so that stray characters such as the `:` in times do not pass for delimiters.
+                let mode = counts[0];
+                if mode < 2 || counts.iter().any(|count| *count != mode) {
+                    continue;
+                }
+
+                let is_better = match &best {
+                    None => true,
+                    Some((best_quoted, best_mode, _)) => {
+                        (quoted_fields, mode) > (*best_quoted, *best_mode)
+                    }
+                };
+                if is_better {
+                    best = Some((quoted_fields, mode, dialect));
+                }
+            }
+        }
+        best.map(|(_, _, dialect)| dialect)
+    }
+
+    /// Guesses whether the first record of `sample` is a header.
+    ///
+    /// Each column votes by comparing the first record with the records below it: a column
+    /// whose values are all numeric, or all of the same length, votes for a header when the
+    /// first value breaks that pattern and against one otherwise. Without any evidence the
+    /// first record is assumed to be a header.
+    pub fn has_header(&self, sample: &str) -> bool {
+        let records: Vec<Vec<String>> = self
+            .split_records(sample)
+            .iter()
+            .take(SNIFF_RECORDS)
+            .map(|record| self.parse_record(record))
+            .collect();
+        let Some((header, rows)) = records.split_first() else {
+            return true;
+        };
+        if rows.is_empty() {
+            return true;
+        }
+
+        let mut votes: i32 = 0;
+        for (col, title) in header.iter().enumerate() {
+            let values: Vec<&str> = rows
+                .iter()
+                .filter_map(|row| row.get(col))
+                .map(|value| value.as_str())
+                .filter(|value| !value.is_empty() && *value != "_")
+                .collect();
+            if values.is_empty() {
+                continue;
+            }
+            let is_number = |value: &str| value.trim().parse::<f64>().is_ok();
+            if values.iter().all(|value| is_number(value)) {
+                votes += if is_number(title) { -1 } else { 1 };
+            } else {
+                let length = values[0].chars().count();
+                if values.iter().all(|value| value.chars().count() == length) {
+                    votes += if title.chars().count() == length {
+                        -1
+                    } else {
+                        1
+                    };
+                }
+            }
+        }
+        votes >= 0
+    }
+
+    /// Sniffs the dialect and header of `sample`. See [`Dialect::sniff`] and
+    /// [`Dialect::has_header`].
+    pub fn detect(sample: &str) -> Option<Detection> {
+        let dialect = Dialect::sniff(sample)?;
+        let has_header = dialect.has_header(sample);
+        Some(Detection {
+            dialect,
+            has_header,
+        })
+    }
+}
+
+impl Display for Dialect {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "delimiter {:?}, quote {:?}, escape {}, line endings {}",
+            self.delimiter,
+            self.quote,
+            self.escape
+                .map_or("none".to_owned(), |escape| format!("{:?}", escape)),
+            self.line_terminator
+        )
+    }
+}
@@ -0,0 +1,148 @@
+use std::fmt::{Display, Formatter};
+
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, WINDOWS_1252};
+
+use crate::CSVError;
+
+/// Number of bytes inspected when checking whether a file without a byte order mark is UTF-16.
+const SNIFF_BYTES: usize = 4096;
+
+/// Character encoding a file is read and written in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TextEncoding {
+    #[default]
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    /// ISO-8859-1, where every byte is the character with the same code point.
+    Latin1,
+    /// The superset of Latin-1 written by most Windows software, with printable characters
+    /// such as `€` in place of control characters.
+    Windows1252,
+}
+
+impl TextEncoding {
+    /// Parses an encoding name such as `utf-8`, `utf-16le`, `utf-16be`, `latin1` or
+    /// `windows-1252`, in any case and with or without dashes. `utf-16` is little endian.
+    pub fn parse(label: &str) -> Option<Self> {
+        let label: String = label
+            .trim()
+            .chars()
+            .filter(|ch| !matches!(ch, '-' | '_'))
+            .collect();
+        match label.to_ascii_lowercase().as_str() {
+            "utf8" => Some(TextEncoding::Utf8),
+            "utf16" | "utf16le" => Some(TextEncoding::Utf16Le),
+            "utf16be" => Some(TextEncoding::Utf16Be),
+            "latin1" | "iso88591" => Some(TextEncoding::Latin1),
+            "windows1252" | "cp1252" => Some(TextEncoding::Windows1252),
+            _ => None,
+        }
+    }
+
+    /// Guesses the encoding of `bytes`, the whole content of a file.
+    ///
+    /// A UTF-16 byte order mark picks UTF-16. So do zero bytes, which ASCII characters such as
+    /// delimiters and line breaks have in UTF-16, in at least one in eight of the odd or even
+    /// positions and rarely in the others, where characters such as `Ā` leave a few. Otherwise
+    /// content that is valid UTF-8 is UTF-8, and anything else is taken to be Windows-1252.
+    pub fn detect(bytes: &[u8]) -> Self {
+        match bytes {
+            [0xFF, 0xFE, ..] => return TextEncoding::Utf16Le,
+            [0xFE, 0xFF, ..] => return TextEncoding::Utf16Be,
+            _ => {}
+        }
+        let sample = &bytes[..bytes.len().min(SNIFF_BYTES)];
+        let zeros = |offset: usize| {
+            sample
+                .iter()
+                .skip(offset)
+                .step_by(2)
+                .filter(|&&byte| byte == 0)
+                .count()
+        };
+        let (even, odd, pairs) = (zeros(0), zeros(1), sample.len() / 2);
+        let mostly_zero =
+            |zeros: usize, others: usize| zeros > 0 && zeros * 8 >= pairs && others * 4 <= zeros;
+        if mostly_zero(odd, even) {
+            TextEncoding::Utf16Le
+        } else if mostly_zero(even, odd) {
+            TextEncoding::Utf16Be
+        } else if std::str::from_utf8(bytes).is_ok() {
+            TextEncoding::Utf8
+        } else {
+            TextEncoding::Windows1252
+        }
+    }
+
+    /// Returns `true` for the encodings that can represent every character, and so can start
+    /// with a byte order mark.
+    pub fn is_unicode(&self) -> bool {
+        matches!(
+            self,
+            TextEncoding::Utf8 | TextEncoding::Utf16Le | TextEncoding::Utf16Be
+        )
+    }
+
+    /// Decodes `bytes` into text. A byte order mark is kept as the first character.
+    pub(crate) fn decode(&self, bytes: Vec<u8>) -> Result<String, CSVError> {
+        let encoding: &Encoding = match self {
+            TextEncoding::Utf8 => {
+                return String::from_utf8(bytes).map_err(|_| CSVError::InvalidEncoding)
+            }
+            TextEncoding::Latin1 => return Ok(bytes.into_iter().map(char::from).collect()),
+            TextEncoding::Utf16Le => UTF_16LE,
+            TextEncoding::Utf16Be => UTF_16BE,
+            TextEncoding::Windows1252 => WINDOWS_1252,
+        };
+        encoding
+            .decode_without_bom_handling_and_without_replacement(&bytes)
+            .map(|text| text.into_owned())
+            .ok_or(CSVError::InvalidEncoding)
+    }
+
+    /// Encodes `text` into bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CSVError::Unencodable`] with the first character the encoding cannot represent.
+    ///
+    pub(crate) fn encode(&self, text: &str) -> Result<Vec<u8>, CSVError> {
+        let unencodable = |ch: char| CSVError::Unencodable(format!("'{}' in {}", ch, self));
+        match self {
+            TextEncoding::Utf8 => Ok(text.as_bytes().to_vec()),
+            TextEncoding::Utf16Le => Ok(text.encode_utf16().flat_map(u16::to_le_bytes).collect()),
+            TextEncoding::Utf16Be => Ok(text.encode_utf16().flat_map(u16::to_be_bytes).collect()),
+            TextEncoding::Latin1 => text
+                .chars()
+                .map(|ch| u8::try_from(ch).map_err(|_| unencodable(ch)))
+                .collect(),
+            TextEncoding::Windows1252 => {
+                let (bytes, _, had_errors) = WINDOWS_1252.encode(text);
+                if !had_errors {
+                    return Ok(bytes.into_owned());
+                }
+                // The encoder replaces unmappable characters, so find the first one to report.
+                let mut buffer = [0; 4];
+                let ch = text
+                    .chars()
+                    .find(|ch| WINDOWS_1252.encode(ch.encode_utf8(&mut buffer)).2)
+                    .unwrap_or(char::REPLACEMENT_CHARACTER);
+                Err(unencodable(ch))
+            }
+        }
+    }
+}
+
+impl Display for TextEncoding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            TextEncoding::Utf8 => "UTF-8",
+            TextEncoding::Utf16Le => "UTF-16LE",
+            TextEncoding::Utf16Be => "UTF-16BE",
+            TextEncoding::Latin1 => "ISO-8859-1",
+            TextEncoding::Windows1252 => "Windows-1252",
+        };
+        write!(f, "{}", name)
+    }
+}
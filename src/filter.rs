@@ -0,0 +1,395 @@
+use std::{cmp::Ordering, iter::Peekable, str::Chars};
+
+use crate::{CSVError, Cell, ColumnType};
+
+/// A boolean expression over named columns, used to select rows.
+///
+/// ```text
+/// age > 30 and city = "Pune"
+/// not (status in ("closed", "void") or notes is empty)
+/// name like "A%"
+/// ```
+///
+/// Supported operators are `=`, `!=`, `<`, `<=`, `>`, `>=`, `in (...)`, `like` (with `%`
+/// matching any run of characters and `_` a single one) and `is [not] empty`, combined with
+/// `and`, `or`, `not` and parentheses. Values are compared according to the [`ColumnType`] of
+/// their column: numerically in integer and float columns, chronologically in date columns,
+/// `false` before `true` in boolean columns and as text otherwise. Comparisons, `in` and `like`
+/// are false for `_` (NULL) entries, which are considered empty, as are comparisons and `in` for
+/// blank entries of columns other than text.
+///
+/// Column names and values containing spaces or operator characters can be quoted with `"` or
+/// `'`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter {
+    root: Node,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Compare(String, CompareOp, String),
+    In(String, Vec<String>),
+    Like(String, String),
+    IsEmpty(String),
+    Not(Box<Node>),
+    And(Box<Node>, Box<Node>),
+    Or(Box<Node>, Box<Node>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Op(CompareOp),
+    LParen,
+    RParen,
+    Comma,
+}
+
+impl Filter {
+    /// Parses a filter expression.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CSVError::InvalidFilter`] if the expression is malformed.
+    ///
+    pub fn parse(expression: &str) -> Result<Self, CSVError> {
+        let tokens = tokenize(expression)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let root = parser.or()?;
+        match parser.tokens.get(parser.pos) {
+            None => Ok(Filter { root }),
+            Some(token) => Err(invalid(format!("unexpected {}", describe(token)))),
+        }
+    }
+
+    /// Returns the names of the columns the expression refers to.
+    pub fn columns(&self) -> Vec<&str> {
+        let mut columns = Vec::new();
+        self.root.collect_columns(&mut columns);
+        columns
+    }
+
+    /// Returns whether `row` satisfies the expression. `column` maps a column name returned by
+    /// [`Filter::columns`] to its 0-based position in the row and its type.
+    pub fn matches(&self, row: &[Cell], column: &dyn Fn(&str) -> (usize, ColumnType)) -> bool {
+        self.root.matches(row, column)
+    }
+}
+
+impl Node {
+    fn collect_columns<'a>(&'a self, columns: &mut Vec<&'a str>) {
+        match self {
+            Node::Compare(column, _, _)
+            | Node::In(column, _)
+            | Node::Like(column, _)
+            | Node::IsEmpty(column) => {
+                if !columns.contains(&column.as_str()) {
+                    columns.push(column);
+                }
+            }
+            Node::Not(node) => node.collect_columns(columns),
+            Node::And(left, right) | Node::Or(left, right) => {
+                left.collect_columns(columns);
+                right.collect_columns(columns);
+            }
+        }
+    }
+
+    fn matches(&self, row: &[Cell], column_of: &dyn Fn(&str) -> (usize, ColumnType)) -> bool {
+        let cell = |column: &str| &row[column_of(column).0];
+        let column_type = |column: &str| column_of(column).1;
+        match self {
+            Node::Compare(column, op, value) => match cell(column) {
+                Cell::Null => false,
+                Cell::Value(entry) => {
+                    compare(entry, value, column_type(column)).is_some_and(|ordering| match op {
+                        CompareOp::Eq => ordering == Ordering::Equal,
+                        CompareOp::Ne => ordering != Ordering::Equal,
+                        CompareOp::Lt => ordering == Ordering::Less,
+                        CompareOp::Le => ordering != Ordering::Greater,
+                        CompareOp::Gt => ordering == Ordering::Greater,
+                        CompareOp::Ge => ordering != Ordering::Less,
+                    })
+                }
+            },
+            Node::In(column, values) => match cell(column) {
+                Cell::Null => false,
+                Cell::Value(entry) => values.iter().any(|value| {
+                    compare(entry, value, column_type(column)) == Some(Ordering::Equal)
+                }),
+            },
+            Node::Like(column, pattern) => match cell(column) {
+                Cell::Null => false,
+                Cell::Value(entry) => like(entry, pattern),
+            },
+            Node::IsEmpty(column) => match cell(column) {
+                Cell::Null => true,
+                Cell::Value(entry) => entry.trim().is_empty(),
+            },
+            Node::Not(node) => !node.matches(row, column_of),
+            Node::And(left, right) => left.matches(row, column_of) && right.matches(row, column_of),
+            Node::Or(left, right) => left.matches(row, column_of) || right.matches(row, column_of),
+        }
+    }
+}
+
+/// Compares an entry with a value as the type of the entry's column, falling back to text when
+/// the value does not parse as that type. Blank entries of columns other than text are nulls and
+/// compare with nothing.
+fn compare(entry: &str, value: &str, column_type: ColumnType) -> Option<Ordering> {
+    if column_type != ColumnType::Text && entry.trim().is_empty() {
+        return None;
+    }
+    Some(
+        column_type
+            .compare(entry, value)
+            .unwrap_or_else(|| entry.cmp(value)),
+    )
+}
+
+/// Matches `text` against a SQL `like` pattern.
+fn like(text: &str, pattern: &str) -> bool {
+    let text: Vec<char> = text.chars().collect();
+    let pattern: Vec<char> = pattern.chars().collect();
+    // matched[j] is true when the text read so far matches the first j pattern characters.
+    let mut matched = vec![false; pattern.len() + 1];
+    matched[0] = true;
+    for j in 0..pattern.len() {
+        matched[j + 1] = matched[j] && pattern[j] == '%';
+    }
+    for ch in text {
+        let mut next = vec![false; pattern.len() + 1];
+        for j in 0..pattern.len() {
+            next[j + 1] = match pattern[j] {
+                '%' => next[j] || matched[j + 1],
+                '_' => matched[j],
+                expected => matched[j] && expected == ch,
+            };
+        }
+        matched = next;
+    }
+    matched[pattern.len()]
+}
+
+fn invalid(message: String) -> CSVError {
+    CSVError::InvalidFilter(message)
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Word(word) => format!("'{}'", word),
+        Token::Quoted(text) => format!("\"{}\"", text),
+        Token::Op(_) => "comparison operator".to_owned(),
+        Token::LParen => "'('".to_owned(),
+        Token::RParen => "')'".to_owned(),
+        Token::Comma => "','".to_owned(),
+    }
+}
+
+fn tokenize(expression: &str) -> Result<Vec<Token>, CSVError> {
+    let mut tokens = Vec::new();
+    let mut chars = expression.chars().peekable();
+    while let Some(&ch) = chars.peek() {
+        match ch {
+            _ if ch.is_whitespace() => {
+                chars.next();
+            }
+            '(' | ')' | ',' => {
+                chars.next();
+                tokens.push(match ch {
+                    '(' => Token::LParen,
+                    ')' => Token::RParen,
+                    _ => Token::Comma,
+                });
+            }
+            '"' | '\'' => tokens.push(Token::Quoted(quoted(&mut chars)?)),
+            '=' | '!' | '<' | '>' => tokens.push(Token::Op(operator(&mut chars)?)),
+            _ => {
+                let mut word = String::new();
+                while let Some(&ch) = chars.peek() {
+                    if ch.is_whitespace() || "()=!<>,\"'".contains(ch) {
+                        break;
+                    }
+                    word.push(ch);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Reads a quoted string, where a doubled quote stands for the quote itself.
+fn quoted(chars: &mut Peekable<Chars>) -> Result<String, CSVError> {
+    let quote = chars.next().unwrap_or('"');
+    let mut text = String::new();
+    loop {
+        match chars.next() {
+            Some(ch) if ch == quote => {
+                if chars.peek() == Some(&quote) {
+                    chars.next();
+                    text.push(quote);
+                } else {
+                    return Ok(text);
+                }
+            }
+            Some(ch) => text.push(ch),
+            None => return Err(invalid(format!("missing closing {}", quote))),
+        }
+    }
+}
+
+fn operator(chars: &mut Peekable<Chars>) -> Result<CompareOp, CSVError> {
+    let first = chars.next().unwrap_or('=');
+    let second = chars.peek().copied();
+    let (op, consumed) = match (first, second) {
+        ('=', Some('=')) => (CompareOp::Eq, true),
+        ('=', _) => (CompareOp::Eq, false),
+        ('!', Some('=')) => (CompareOp::Ne, true),
+        ('<', Some('>')) => (CompareOp::Ne, true),
+        ('<', Some('=')) => (CompareOp::Le, true),
+        ('<', _) => (CompareOp::Lt, false),
+        ('>', Some('=')) => (CompareOp::Ge, true),
+        ('>', _) => (CompareOp::Gt, false),
+        _ => return Err(invalid(format!("unknown operator '{}'", first))),
+    };
+    if consumed {
+        chars.next();
+    }
+    Ok(op)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.tokens.get(self.pos), Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.peek_keyword(keyword);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), CSVError> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(invalid(format!(
+                "expected {} but found {}",
+                describe(&expected),
+                describe(&token)
+            ))),
+            None => Err(invalid(format!(
+                "expected {} at the end",
+                describe(&expected)
+            ))),
+        }
+    }
+
+    /// Reads a column name or a value, either bare or quoted.
+    fn operand(&mut self, what: &str) -> Result<String, CSVError> {
+        match self.next() {
+            Some(Token::Word(word)) | Some(Token::Quoted(word)) => Ok(word),
+            Some(token) => Err(invalid(format!(
+                "expected {} but found {}",
+                what,
+                describe(&token)
+            ))),
+            None => Err(invalid(format!("expected {} at the end", what))),
+        }
+    }
+
+    fn or(&mut self) -> Result<Node, CSVError> {
+        let mut node = self.and()?;
+        while self.eat_keyword("or") {
+            node = Node::Or(Box::new(node), Box::new(self.and()?));
+        }
+        Ok(node)
+    }
+
+    fn and(&mut self) -> Result<Node, CSVError> {
+        let mut node = self.unary()?;
+        while self.eat_keyword("and") {
+            node = Node::And(Box::new(node), Box::new(self.unary()?));
+        }
+        Ok(node)
+    }
+
+    fn unary(&mut self) -> Result<Node, CSVError> {
+        if self.eat_keyword("not") {
+            return Ok(Node::Not(Box::new(self.unary()?)));
+        }
+        if self.tokens.get(self.pos) == Some(&Token::LParen) {
+            self.pos += 1;
+            let node = self.or()?;
+            self.expect(Token::RParen)?;
+            return Ok(node);
+        }
+        self.predicate()
+    }
+
+    fn predicate(&mut self) -> Result<Node, CSVError> {
+        let column = self.operand("a column name")?;
+        if let Some(Token::Op(op)) = self.tokens.get(self.pos) {
+            let op = *op;
+            self.pos += 1;
+            return Ok(Node::Compare(column, op, self.operand("a value")?));
+        }
+        if self.eat_keyword("is") {
+            let negated = self.eat_keyword("not");
+            if !self.eat_keyword("empty") {
+                return Err(invalid("expected 'empty' after 'is'".to_owned()));
+            }
+            let node = Node::IsEmpty(column);
+            return Ok(if negated {
+                Node::Not(Box::new(node))
+            } else {
+                node
+            });
+        }
+        let negated = self.eat_keyword("not");
+        let node = if self.eat_keyword("in") {
+            self.expect(Token::LParen)?;
+            let mut values = vec![self.operand("a value")?];
+            while self.tokens.get(self.pos) == Some(&Token::Comma) {
+                self.pos += 1;
+                values.push(self.operand("a value")?);
+            }
+            self.expect(Token::RParen)?;
+            Node::In(column, values)
+        } else if self.eat_keyword("like") {
+            Node::Like(column, self.operand("a pattern")?)
+        } else {
+            return Err(invalid(format!("expected an operator after '{}'", column)));
+        };
+        Ok(if negated {
+            Node::Not(Box::new(node))
+        } else {
+            node
+        })
+    }
+}
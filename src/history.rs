@@ -0,0 +1,111 @@
+use crate::Cell;
+
+/// Number of operations kept for [`CSVFile::undo`](crate::CSVFile::undo).
+pub const HISTORY_LIMIT: usize = 100;
+
+/// One change made by a mutation primitive, recorded as the edit that reverts it. Applying it
+/// records in turn the edit that reverts the revert, so each operation keeps a single copy of
+/// the data it changed, whether it is waiting to be undone or redone.
+#[derive(Debug, Clone)]
+pub(crate) enum Edit {
+    ReplaceCell {
+        row: usize,
+        col: usize,
+        cell: Cell,
+    },
+    InsertRow {
+        row: usize,
+        cells: Vec<Cell>,
+    },
+    RemoveRow {
+        row: usize,
+    },
+    InsertColumn {
+        col: usize,
+        title: String,
+        cells: Vec<Cell>,
+    },
+    RemoveColumn {
+        col: usize,
+    },
+    PermuteRows {
+        order: Vec<usize>,
+    },
+    ReplaceTable {
+        header: Vec<String>,
+        rows: Vec<Vec<Cell>>,
+    },
+}
+
+/// A mutation as a whole, such as deleting a column, made of the edits that revert it.
+#[derive(Debug, Clone)]
+pub(crate) struct Operation {
+    pub(crate) description: String,
+    pub(crate) edits: Vec<Edit>,
+}
+
+/// The journal of the operations applied to a file, for undo and redo.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct History {
+    /// Edits made since the last operation was committed.
+    pending: Vec<Edit>,
+    done: Vec<Operation>,
+    undone: Vec<Operation>,
+}
+
+impl History {
+    pub(crate) fn record(&mut self, edit: Edit) {
+        self.pending.push(edit);
+    }
+
+    /// Groups the pending edits into an operation that can be undone, forgetting the operations
+    /// undone before it.
+    pub(crate) fn commit(&mut self, description: String) {
+        if self.pending.is_empty() {
+            return;
+        }
+        let edits = std::mem::take(&mut self.pending);
+        self.done.push(Operation { description, edits });
+        if self.done.len() > HISTORY_LIMIT {
+            self.done.remove(0);
+        }
+        self.undone.clear();
+    }
+
+    /// Takes the edits recorded since the last commit, such as those that revert an operation
+    /// while it is being undone or redone.
+    pub(crate) fn take_pending(&mut self) -> Vec<Edit> {
+        std::mem::take(&mut self.pending)
+    }
+
+    pub(crate) fn pop_done(&mut self) -> Option<Operation> {
+        self.done.pop()
+    }
+
+    pub(crate) fn pop_undone(&mut self) -> Option<Operation> {
+        self.undone.pop()
+    }
+
+    pub(crate) fn push_done(&mut self, operation: Operation) {
+        self.done.push(operation);
+    }
+
+    pub(crate) fn push_undone(&mut self, operation: Operation) {
+        self.undone.push(operation);
+    }
+
+    pub(crate) fn done(&self) -> impl Iterator<Item = &str> {
+        self.done
+            .iter()
+            .map(|operation| operation.description.as_str())
+    }
+
+    /// Returns the undone operations in the order they were made, which is also the order they
+    /// are redone in.
+    pub(crate) fn undone(&self) -> impl Iterator<Item = &str> {
+        self.undone
+            .iter()
+            .rev()
+            .map(|operation| operation.description.as_str())
+    }
+}
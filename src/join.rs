@@ -0,0 +1,179 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{Display, Formatter},
+    path::Path,
+};
+
+use crate::{column::split_outside_quotes, schema::is_null, CSVError, Cell, ColumnSelector};
+
+/// Which rows a join keeps besides the ones whose keys match.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JoinKind {
+    /// Only rows with a match in both files.
+    #[default]
+    Inner,
+    /// Every row of the loaded file, with nulls where the other file has no match.
+    Left,
+    /// Every row of the other file, with nulls where the loaded file has no match.
+    Right,
+    /// Every row of both files.
+    Full,
+}
+
+impl JoinKind {
+    /// Parses `inner`, `left`, `right` or `full`, in any case.
+    pub fn parse(text: &str) -> Option<Self> {
+        match text.trim().to_ascii_lowercase().as_str() {
+            "inner" => Some(JoinKind::Inner),
+            "left" => Some(JoinKind::Left),
+            "right" => Some(JoinKind::Right),
+            "full" => Some(JoinKind::Full),
+            _ => None,
+        }
+    }
+}
+
+impl Display for JoinKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            JoinKind::Inner => "inner",
+            JoinKind::Left => "left",
+            JoinKind::Right => "right",
+            JoinKind::Full => "full",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A pair of columns whose entries must be equal for two rows to be joined.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JoinKey {
+    /// The column of the loaded file.
+    pub left: ColumnSelector,
+    /// The column of the other file.
+    pub right: ColumnSelector,
+}
+
+impl JoinKey {
+    /// Parses a comma-separated list of keys. Each key is either a column present in both
+    /// files, such as `id`, or a pair of columns, such as `id = customer_id`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CSVError::InvalidJoinKey`] if no key is given or a key has more than one `=`.
+    ///
+    pub fn parse_list(spec: &str) -> Result<Vec<JoinKey>, CSVError> {
+        let keys: Vec<&str> = split_outside_quotes(spec, |ch| ch == ',');
+        if keys.is_empty() {
+            return Err(CSVError::InvalidJoinKey(spec.trim().to_owned()));
+        }
+        keys.into_iter()
+            .map(|key| match split_outside_quotes(key, |ch| ch == '=')[..] {
+                [column] => Ok(JoinKey {
+                    left: ColumnSelector::parse(column),
+                    right: ColumnSelector::parse(column),
+                }),
+                [left, right] => Ok(JoinKey {
+                    left: ColumnSelector::parse(left),
+                    right: ColumnSelector::parse(right),
+                }),
+                _ => Err(CSVError::InvalidJoinKey(key.to_owned())),
+            })
+            .collect()
+    }
+}
+
+/// Joins the rows of two tables on the 0-based key column pairs `keys`.
+///
+/// Rows come in the order of the left table, each followed by its matches in the order of the
+/// right table, then the unmatched right rows for right and full joins. The right key columns
+/// are left out of the result and fill the left key columns of unmatched right rows. Right
+/// columns whose title is already taken are prefixed with the stem of `right_path`. Null keys
+/// never match.
+pub(crate) fn join(
+    left: (&[String], &[Vec<Cell>]),
+    right: (&[String], &[Vec<Cell>]),
+    right_path: &str,
+    kind: JoinKind,
+    keys: &[(usize, usize)],
+) -> (Vec<String>, Vec<Vec<Cell>>) {
+    let (left_header, left_rows) = left;
+    let (right_header, right_rows) = right;
+    let right_keys: HashSet<usize> = keys.iter().map(|&(_, col)| col).collect();
+    let right_cols: Vec<usize> = (0..right_header.len())
+        .filter(|col| !right_keys.contains(col))
+        .collect();
+
+    let stem = Path::new(right_path)
+        .file_stem()
+        .map_or("right".to_owned(), |stem| {
+            stem.to_string_lossy().into_owned()
+        });
+    let mut header = left_header.to_vec();
+    for &col in right_cols.iter() {
+        let mut title = right_header[col].clone();
+        if header.contains(&title) {
+            title = format!("{}.{}", stem, right_header[col]);
+            let mut suffix = 2;
+            while header.contains(&title) {
+                title = format!("{}.{}_{}", stem, right_header[col], suffix);
+                suffix += 1;
+            }
+        }
+        header.push(title);
+    }
+
+    let key_of = |row: &[Cell], side: fn(&(usize, usize)) -> usize| -> Option<Vec<String>> {
+        keys.iter()
+            .map(|key| {
+                let cell = &row[side(key)];
+                (!is_null(cell)).then(|| cell.as_str().to_owned())
+            })
+            .collect()
+    };
+    let mut matches: HashMap<Vec<String>, Vec<usize>> = HashMap::new();
+    for (row, cells) in right_rows.iter().enumerate() {
+        if let Some(key) = key_of(cells, |&(_, col)| col) {
+            matches.entry(key).or_default().push(row);
+        }
+    }
+
+    let right_part = |row: Option<&Vec<Cell>>| -> Vec<Cell> {
+        right_cols
+            .iter()
+            .map(|&col| row.map_or(Cell::Null, |cells| cells[col].clone()))
+            .collect()
+    };
+    let mut rows = Vec::new();
+    let mut matched = vec![false; right_rows.len()];
+    for cells in left_rows.iter() {
+        let found = key_of(cells, |&(col, _)| col)
+            .and_then(|key| matches.get(&key))
+            .map_or(&[][..], Vec::as_slice);
+        for &row in found {
+            matched[row] = true;
+            let mut joined = cells.clone();
+            joined.extend(right_part(Some(&right_rows[row])));
+            rows.push(joined);
+        }
+        if found.is_empty() && matches!(kind, JoinKind::Left | JoinKind::Full) {
+            let mut joined = cells.clone();
+            joined.extend(right_part(None));
+            rows.push(joined);
+        }
+    }
+    if matches!(kind, JoinKind::Right | JoinKind::Full) {
+        for (row, cells) in right_rows.iter().enumerate() {
+            if matched[row] {
+                continue;
+            }
+            let mut joined = vec![Cell::Null; left_header.len()];
+            for &(left_col, right_col) in keys {
+                joined[left_col] = cells[right_col].clone();
+            }
+            joined.extend(right_part(Some(cells)));
+            rows.push(joined);
+        }
+    }
+    (header, rows)
+}
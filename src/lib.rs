@@ -1,12 +1,60 @@
 use std::error::Error;
 use std::{
+    cmp::Ordering,
+    collections::HashMap,
     fmt::{Display, Formatter},
-    fs, io,
-    io::Write,
+    fs,
+    io::{self, Read, Write},
     path::Path,
-    process,
 };
 
+#[macro_use]
+mod status;
+
+mod aggregate;
+mod cell;
+mod column;
+mod confirm;
+mod dialect;
+mod encoding;
+mod filter;
+mod history;
+mod join;
+mod parser;
+mod render;
+mod save;
+mod schema;
+mod sort;
+mod stats;
+mod value;
+mod widths;
+
+pub use aggregate::{Aggregate, AggregateFunction};
+pub use cell::Cell;
+pub use column::ColumnSelector;
+use confirm::Confirmer;
+pub use confirm::{AlwaysNo, AlwaysYes, CallbackConfirm, Confirm, TerminalConfirm};
+pub use dialect::{Detection, Dialect, Framing, LineTerminator, BOM};
+pub use encoding::TextEncoding;
+pub use filter::Filter;
+pub use history::HISTORY_LIMIT;
+use history::{Edit, History, Operation};
+pub use join::{JoinKey, JoinKind};
+use render::Renderer;
+pub use render::{BoxedTableRenderer, Table, TableRenderer};
+pub use save::Backup;
+use schema::is_null;
+pub use schema::{ColumnSchema, ColumnType};
+use sort::ResolvedKey;
+pub use sort::SortKey;
+pub use stats::{ColumnStats, TOP_VALUES};
+pub use status::StatusOutput;
+pub use widths::display_width;
+use widths::ColumnWidths;
+
+/// Number of bytes at the start of a file inspected when sniffing its dialect.
+const SNIFF_SAMPLE_BYTES: usize = 64 * 1024;
+
 pub trait FileDataUtils {
     // display file x y
     fn display_file(
@@ -23,12 +71,16 @@ pub trait FileDataUtils {
     }
 
     // delete column x
-    fn delete_column(&mut self, _col_index: usize) -> Result<(), Box<dyn Error>> {
+    fn delete_column(&mut self, _column: impl Into<ColumnSelector>) -> Result<(), Box<dyn Error>> {
         Ok(())
     }
 
     // delete entry x y
-    fn delete_entry(&mut self, _row_index: usize, _col_index: usize) -> Result<(), Box<dyn Error>> {
+    fn delete_entry(
+        &mut self,
+        _row_index: usize,
+        _column: impl Into<ColumnSelector>,
+    ) -> Result<(), Box<dyn Error>> {
         Ok(())
     }
 
@@ -44,7 +96,7 @@ pub trait FileDataUtils {
     // modify column x string
     fn modify_column(
         &mut self,
-        _col_index: usize,
+        _column: impl Into<ColumnSelector>,
         _new_data: String,
     ) -> Result<(), Box<dyn Error>> {
         Ok(())
@@ -54,7 +106,7 @@ pub trait FileDataUtils {
     fn update_entry(
         &mut self,
         _row_index: usize,
-        _col_index: usize,
+        _column: impl Into<ColumnSelector>,
         _new_value: String,
     ) -> Result<(), Box<dyn Error>> {
         Ok(())
@@ -65,11 +117,52 @@ pub trait FileDataUtils {
         Ok(())
     }
 
+    // merge fileName by name [add]
+    fn merge_files_with_options(
+        &mut self,
+        _other: &Self,
+        _options: &MergeOptions,
+    ) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    // merge glob
+    fn merge_many_files(
+        &mut self,
+        _others: &[Self],
+        _options: &MergeOptions,
+    ) -> Result<(), Box<dyn Error>>
+    where
+        Self: Sized,
+    {
+        Ok(())
+    }
+
+    // join [kind] fileName on keys
+    fn join_files(
+        &mut self,
+        _other: &Self,
+        _kind: JoinKind,
+        _on: &[JoinKey],
+    ) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
     // sort rows
     fn sorted_display(&self) -> Result<(), Box<dyn Error>> {
         Ok(())
     }
 
+    // sort by keys
+    fn display_sorted_by(&self, _keys: &str) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    // sort by keys apply
+    fn sort_by(&mut self, _keys: &str) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
     // add row string
     fn add_row(&mut self, _new_row_data: String) -> Result<(), Box<dyn Error>> {
         Ok(())
@@ -81,7 +174,7 @@ pub trait FileDataUtils {
     }
 
     // display column x
-    fn display_column(&self, _col_index: usize) -> Result<(), Box<dyn Error>> {
+    fn display_column(&self, _column: impl Into<ColumnSelector>) -> Result<(), Box<dyn Error>> {
         Ok(())
     }
 
@@ -89,26 +182,133 @@ pub trait FileDataUtils {
     fn display_row(&self, _row_index: usize) -> Result<(), Box<dyn Error>> {
         Ok(())
     }
+
+    // schema
+    fn display_schema(&self) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    // stats col x
+    fn display_column_stats(
+        &self,
+        _column: impl Into<ColumnSelector>,
+    ) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    // stats file
+    fn display_stats(&self) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    // undo
+    fn undo(&mut self) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    // redo
+    fn redo(&mut self) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    // history
+    fn display_history(&self) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    // display where expression
+    fn display_where(&self, _filter: &str) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    // delete where expression
+    fn delete_where(&mut self, _filter: &str) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    // modify where expression set column = value
+    fn modify_where(
+        &mut self,
+        _filter: &str,
+        _column: impl Into<ColumnSelector>,
+        _new_value: String,
+    ) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
 }
 
 #[derive(Default, Debug, Clone)]
 pub struct CSVFile {
-    pub file_data: Vec<String>,
-    pub header: String,
-    pub num_rows: usize,
-    pub num_cols: usize,
+    header: Vec<String>,
+    rows: Vec<Vec<Cell>>,
     pub file_path: String,
-    pub max_col_lengths: Vec<usize>,
+    widths: ColumnWidths,
+    pub dialect: Dialect,
+    /// The byte order mark and final line break of the file, reproduced when it is written.
+    pub framing: Framing,
+    /// The encoding the file was loaded in and is written in.
+    pub encoding: TextEncoding,
+    /// Whether the file has no header row. Its titles are then generated as `col1` to `colN`
+    /// and are not written with the rows.
+    pub headerless: bool,
+    pub detection: Option<Detection>,
+    pub status_output: StatusOutput,
+    /// Whether [`write_to_file`](CSVFile::write_to_file) keeps the previous version of the file.
+    pub backup: Backup,
+    confirm: Confirmer,
+    renderer: Renderer,
+    history: History,
+    modified: bool,
+    load_options: LoadOptions,
+}
+
+/// Options controlling how [`FileDataUtils::merge_files_with_options`] matches columns.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergeOptions {
+    /// Whether to match columns by title instead of by position.
+    pub by_name: bool,
+    /// Whether to add the columns only the other file has when matching by title.
+    pub add_columns: bool,
+    /// Title of a column recording the path of the file each row came from. It is added to the
+    /// loaded file if missing, filled with its own path, and is not matched against the other
+    /// file's columns.
+    pub source_column: Option<String>,
+}
+
+/// Options controlling how a file is loaded.
+#[derive(Debug, Clone)]
+pub struct LoadOptions {
+    /// Dialect to parse the file with. When `None`, it is sniffed from the file contents or
+    /// picked from the file extension.
+    pub dialect: Option<Dialect>,
+    /// Whether to sniff the dialect from the file contents when `dialect` is `None`.
+    pub sniff: bool,
+    /// Encoding to decode the file with. When `None`, it is detected from the file contents
+    /// (see [`TextEncoding::detect`]).
+    pub encoding: Option<TextEncoding>,
+    /// Whether the first record is data rather than a header. The columns are then titled
+    /// `col1` to `colN`.
+    pub headerless: bool,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        LoadOptions {
+            dialect: None,
+            sniff: true,
+            encoding: None,
+            headerless: false,
+        }
+    }
 }
 
 impl PartialEq for CSVFile {
     fn eq(&self, other: &Self) -> bool {
-        self.file_data == other.file_data
-            && self.header == other.header
-            && self.num_rows == other.num_rows
-            && self.num_cols == other.num_cols
+        self.header == other.header
+            && self.rows == other.rows
             && self.file_path == other.file_path
-            && self.max_col_lengths == other.max_col_lengths
+            && self.dialect == other.dialect
+            && self.detection == other.detection
     }
 }
 
@@ -126,6 +326,30 @@ impl From<CSVError> for String {
             CSVError::InvalidDimensions => {
                 "Dimensions of specified files do not match. Cannot merge".to_owned()
             }
+            CSVError::FileNotFound => "File doesn't exist".to_owned(),
+            CSVError::Io(err) => format!("Could not read file: {}", err),
+            CSVError::InvalidEncoding => "File is not valid text in its encoding".to_owned(),
+            CSVError::EmptyFile => "File is empty".to_owned(),
+            CSVError::RaggedRow(row, entries, expected) => format!(
+                "Row {} has {} entries but the file has {} columns",
+                row, entries, expected
+            ),
+            CSVError::UnknownDialect => {
+                "Could not detect the delimiter. Specify one explicitly".to_owned()
+            }
+            CSVError::InvalidFilter(err) => format!("Invalid filter: {}", err),
+            CSVError::UnknownColumn(name) => {
+                format!("Col with entered name doesn't exist: {}", name)
+            }
+            CSVError::InvalidSortKey(key) => format!("Invalid sort key: {}", key),
+            CSVError::InvalidAggregate(aggregate) => format!("Invalid aggregate: {}", aggregate),
+            CSVError::InvalidJoinKey(key) => format!("Invalid join key: {}", key),
+            CSVError::NothingToUndo => "No change left to undo".to_owned(),
+            CSVError::NothingToRedo => "No undone change left to redo".to_owned(),
+            CSVError::NoDataToWrite => "No data found to write to file".to_owned(),
+            CSVError::MissingExtension => "Invalid file name: missing file extension".to_owned(),
+            CSVError::WriteFailed(err) => format!("Could not write file: {}", err),
+            CSVError::Unencodable(text) => format!("Cannot write the character {}", text),
         }
     }
 }
@@ -138,6 +362,23 @@ pub enum CSVError {
     MoreEntriesThanRequired,
     LessEntriesThanRequired,
     InvalidDimensions,
+    FileNotFound,
+    Io(String),
+    InvalidEncoding,
+    EmptyFile,
+    RaggedRow(usize, usize, usize),
+    UnknownDialect,
+    InvalidFilter(String),
+    UnknownColumn(String),
+    InvalidSortKey(String),
+    InvalidAggregate(String),
+    InvalidJoinKey(String),
+    NothingToUndo,
+    NothingToRedo,
+    NoDataToWrite,
+    MissingExtension,
+    WriteFailed(String),
+    Unencodable(String),
 }
 
 impl std::error::Error for CSVError {}
@@ -157,6 +398,31 @@ impl Display for CSVError {
                 f,
                 "Dimensions of specified files do not match. Cannot merge"
             ),
+            CSVError::FileNotFound => write!(f, "File doesn't exist"),
+            CSVError::Io(err) => write!(f, "Could not read file: {}", err),
+            CSVError::InvalidEncoding => write!(f, "File is not valid text in its encoding"),
+            CSVError::EmptyFile => write!(f, "File is empty"),
+            CSVError::RaggedRow(row, entries, expected) => write!(
+                f,
+                "Row {} has {} entries but the file has {} columns",
+                row, entries, expected
+            ),
+            CSVError::UnknownDialect => {
+                write!(f, "Could not detect the delimiter. Specify one explicitly")
+            }
+            CSVError::InvalidFilter(err) => write!(f, "Invalid filter: {}", err),
+            CSVError::UnknownColumn(name) => {
+                write!(f, "Col with entered name doesn't exist: {}", name)
+            }
+            CSVError::InvalidSortKey(key) => write!(f, "Invalid sort key: {}", key),
+            CSVError::InvalidAggregate(aggregate) => write!(f, "Invalid aggregate: {}", aggregate),
+            CSVError::InvalidJoinKey(key) => write!(f, "Invalid join key: {}", key),
+            CSVError::NothingToUndo => write!(f, "No change left to undo"),
+            CSVError::NothingToRedo => write!(f, "No undone change left to redo"),
+            CSVError::NoDataToWrite => write!(f, "No data found to write to file"),
+            CSVError::MissingExtension => write!(f, "Invalid file name: missing file extension"),
+            CSVError::WriteFailed(err) => write!(f, "Could not write file: {}", err),
+            CSVError::Unencodable(text) => write!(f, "Cannot write the character {}", text),
         }
     }
 }
@@ -178,48 +444,7 @@ impl FileDataUtils for CSVFile {
         start_index: Option<usize>,
         end_index: Option<usize>,
     ) -> Result<(), Box<dyn Error>> {
-        let (s_index, e_index) = (
-            start_index.unwrap_or_else(|| 1),
-            end_index.unwrap_or_else(|| self.file_data.len()),
-        );
-        if s_index > self.num_rows || e_index > self.num_rows || s_index == 0 || e_index == 0 {
-            return Err(Box::new(CSVError::InvalidRowIndex));
-        }
-        if s_index > e_index {
-            return Err(Box::new(CSVError::IncorrectIndices));
-        }
-        // Display first horizontal line
-        self.display_horizontal_line();
-
-        // Display headers
-        print!("|");
-        for (header, max_length) in self
-            .header
-            .split(",")
-            .collect::<Vec<&str>>()
-            .iter()
-            .zip(&self.max_col_lengths)
-        {
-            print!(" \x1b[36m{:<width$} \x1b[0m|", header, width = max_length);
-        }
-        println!();
-
-        // Display second horizontal line
-        self.display_horizontal_line();
-
-        // Display rows
-        for (index, line) in self.file_data[s_index - 1..e_index].iter().enumerate() {
-            let row_items: Vec<&str> = line.split(',').collect();
-            print!("|");
-            for (item, max_length) in row_items.iter().zip(&self.max_col_lengths) {
-                print!(" {:<width$} |", item, width = max_length);
-            }
-            println!("  ({})", index + 1);
-
-            // Display horizontal lines for rows
-            self.display_horizontal_line();
-        }
-        return Ok(());
+        self.render_file(&mut io::stdout().lock(), start_index, end_index)
     }
 
     /// Deletes the specified row from the CSV file.
@@ -233,27 +458,24 @@ impl FileDataUtils for CSVFile {
     /// Returns an error if the provided row index is invalid.
     ///
     fn delete_row(&mut self, row_index: usize) -> Result<(), Box<dyn Error>> {
-        if row_index > self.num_rows || row_index == 0 {
+        if row_index > self.num_rows() || row_index == 0 {
             return Err(Box::new(CSVError::InvalidRowIndex));
         }
-        println!(
-            ">>>  \x1b[35mAre you sure you want to delete this row #{}\x1b[0m",
-            row_index
-        );
-        self.display_file(Some(row_index), Some(row_index))?;
-        print!(">>  \x1b[35m[y/n]:\x1b[0m ");
-        let _ = io::stdout().flush();
-        let mut input = String::new();
-        let _ = io::stdin().read_line(&mut input);
-        if input != "y\n".to_owned() {
-            println!(">>>  \x1b[33mDid Not Delete Row\x1b[0m");
+        let prompt = format!("Are you sure you want to delete this row #{}", row_index);
+        if !self.confirm.confirm(&prompt, &|| {
+            let _ = self.display_file(Some(row_index), Some(row_index));
+        }) {
+            status!(self, ">>>  \x1b[33mDid Not Delete Row\x1b[0m");
             return Ok(());
         }
 
-        let row = self.file_data[row_index - 1].clone();
-        self.file_data.remove(row_index - 1);
-        self.num_rows -= 1;
-        println!(">>>  \x1b[32mSuccessfully Deleted Row: {}\x1b[0m", row);
+        let row = self.remove_row(row_index - 1);
+        self.history.commit(format!("delete row {}", row_index));
+        status!(
+            self,
+            ">>>  \x1b[32mSuccessfully Deleted Row: {}\x1b[0m",
+            Dialect::default().format_record(&row)
+        );
 
         Ok(())
     }
@@ -262,42 +484,26 @@ impl FileDataUtils for CSVFile {
     ///
     /// # Arguments
     ///
-    /// * `col_index` - The index of the column to be deleted.
+    /// * `column` - The index or title of the column to be deleted.
     ///
     /// # Errors
     ///
     /// Returns an error if the provided column index is invalid.
     ///
-    fn delete_column(&mut self, col_index: usize) -> Result<(), Box<dyn Error>> {
-        if col_index > self.num_cols || col_index == 0 {
-            return Err(Box::new(CSVError::InvalidColIndex));
-        }
+    fn delete_column(&mut self, column: impl Into<ColumnSelector>) -> Result<(), Box<dyn Error>> {
+        let col_index = self.column_index(column)?;
 
-        println!(">>>  \x1b[35mAre you sure you want to delete the column:\x1b[0m");
-        self.display_column(col_index)?;
-
-        print!(">>  \x1b[35m[y/n]:\x1b[0m ");
-        let _ = io::stdout().flush();
-        let mut input = String::new();
-        let _ = io::stdin().read_line(&mut input);
-        if input != "y\n".to_owned() {
-            println!(">>>  \x1b[33mDid Not Delete Column\x1b[0m");
+        let prompt = "Are you sure you want to delete the column:";
+        if !self.confirm.confirm(prompt, &|| {
+            let _ = self.display_column(col_index);
+        }) {
+            status!(self, ">>>  \x1b[33mDid Not Delete Column\x1b[0m");
             return Ok(());
         }
 
-        let mut header_vec: Vec<&str> = self.header.split(",").collect();
-        header_vec.remove(col_index - 1);
-        self.header = header_vec.join(",");
-
-        for row_ind in 0..self.num_rows {
-            let row = self.file_data[row_ind].clone();
-            let mut row_vec: Vec<&str> = row.split(",").collect();
-            row_vec.remove(col_index - 1);
-            self.file_data[row_ind] = row_vec.join(",");
-        }
-
-        self.num_cols -= 1;
-        println!(">>>  \x1b[32mSuccessfully Deleted Column\x1b[0m");
+        let title = self.remove_column(col_index - 1);
+        self.history.commit(format!("delete col {}", title));
+        status!(self, ">>>  \x1b[32mSuccessfully Deleted Column\x1b[0m");
         Ok(())
     }
 
@@ -306,42 +512,40 @@ impl FileDataUtils for CSVFile {
     /// # Arguments
     ///
     /// * `row_index` - The index of the row containing the entry to be deleted.
-    /// * `col_index` - The index of the column containing the entry to be deleted.
+    /// * `column` - The index or title of the column containing the entry to be deleted.
     ///
     /// # Errors
     ///
     /// Returns an error if the provided row or column index is invalid, or if the existing entry is NULL(_).
     ///
-    fn delete_entry(&mut self, row_index: usize, col_index: usize) -> Result<(), Box<dyn Error>> {
-        if row_index > self.num_rows || row_index == 0 {
+    fn delete_entry(
+        &mut self,
+        row_index: usize,
+        column: impl Into<ColumnSelector>,
+    ) -> Result<(), Box<dyn Error>> {
+        if row_index > self.num_rows() || row_index == 0 {
             return Err(Box::new(CSVError::InvalidRowIndex));
         }
-        if col_index > self.num_cols || col_index == 0 {
-            return Err(Box::new(CSVError::InvalidColIndex));
-        }
-        let row = self.file_data[row_index - 1].clone();
-        let mut row_vec = row.split(",").collect::<Vec<&str>>();
-        let entry = row_vec.remove(col_index - 1);
-        if entry == "_" {
+        let col_index = self.column_index(column)?;
+        let entry = &self.rows[row_index - 1][col_index - 1];
+        if entry.is_null() {
             return Err(Box::new(CSVError::InvalidEntry));
         }
 
-        println!(
-            ">>>  \x1b[35mAre you sure you want to delete the entry: {}\x1b[0m",
-            entry
-        );
-        print!(">>  \x1b[35m[y/n]:\x1b[0m ");
-        let _ = io::stdout().flush();
-        let mut input = String::new();
-        let _ = io::stdin().read_line(&mut input);
-        if input != "y\n".to_owned() {
-            println!(">>>  \x1b[33mDid Not Delete Entry\x1b[0m");
+        let prompt = format!("Are you sure you want to delete the entry: {}", entry);
+        if !self.confirm.confirm(&prompt, &|| {}) {
+            status!(self, ">>>  \x1b[33mDid Not Delete Entry\x1b[0m");
             return Ok(());
         }
 
-        row_vec.insert(col_index - 1, "_");
-        self.file_data[row_index - 1] = row_vec.join(",");
-        println!(">>>  \x1b[32mSuccessfully Deleted Entry: {}\x1b[0m", entry);
+        let entry = self.replace_cell(row_index - 1, col_index - 1, Cell::Null);
+        self.history
+            .commit(format!("delete item {} {}", row_index, col_index));
+        status!(
+            self,
+            ">>>  \x1b[32mSuccessfully Deleted Entry: {}\x1b[0m",
+            entry
+        );
 
         Ok(())
     }
@@ -358,36 +562,38 @@ impl FileDataUtils for CSVFile {
     /// Returns an error if the provided row index is invalid, or if the row data has an incorrect number of entries.
     ///
     fn modify_row(&mut self, row_index: usize, row_data: String) -> Result<(), Box<dyn Error>> {
-        if row_index > self.num_rows || row_index == 0 {
+        if row_index > self.num_rows() || row_index == 0 {
             return Err(Box::new(CSVError::InvalidRowIndex));
         }
-        let row_entries_count = row_data.split(",").count();
-        if row_entries_count > self.num_cols {
+        let new_row = parse_input(&row_data);
+        if new_row.len() > self.num_cols() {
             return Err(Box::new(CSVError::MoreEntriesThanRequired));
         }
-        if row_entries_count < self.num_cols {
+        if new_row.len() < self.num_cols() {
             return Err(Box::new(CSVError::LessEntriesThanRequired));
         }
 
-        println!(
-            ">>>  \x1b[35mAre you sure you want to modify this row: #{}\x1b[0m",
-            row_index
-        );
-        self.display_file(Some(row_index), Some(row_index))?;
-        print!(">>  \x1b[35m[y/n]:\x1b[0m ");
-        let _ = io::stdout().flush();
-        let mut input = String::new();
-        let _ = io::stdin().read_line(&mut input);
-        if input != "y\n".to_owned() {
-            println!(">>>  \x1b[33mDid Not Modify Row\x1b[0m");
+        let prompt = format!("Are you sure you want to modify this row: #{}", row_index);
+        if !self.confirm.confirm(&prompt, &|| {
+            let _ = self.display_file(Some(row_index), Some(row_index));
+        }) {
+            status!(self, ">>>  \x1b[33mDid Not Modify Row\x1b[0m");
             return Ok(());
         }
-        self.file_data[row_index - 1] = row_data;
-        println!(
+        for (col, cell) in new_row.into_iter().enumerate() {
+            self.replace_cell(row_index - 1, col, cell);
+        }
+        self.history.commit(format!("modify row {}", row_index));
+        status!(
+            self,
             ">>>  \x1b[32mSuccessfully Modified Row #{} Into:\x1b[0m ",
             row_index
         );
-        self.display_file(Some(row_index), Some(row_index))?;
+        self.render_file(
+            &mut self.status_output.writer(),
+            Some(row_index),
+            Some(row_index),
+        )?;
 
         Ok(())
     }
@@ -396,53 +602,47 @@ impl FileDataUtils for CSVFile {
     ///
     /// # Arguments
     ///
-    /// * `col_index` - The index of the column to be modified.
+    /// * `column` - The index or title of the column to be modified.
     /// * `new_entry` - A string containing the new values for the specified column, separated by commas.
     ///
     /// # Errors
     ///
     /// Returns an error if the provided column index is invalid, or if the new entry has an incorrect number of values.
     ///
-    fn modify_column(&mut self, col_index: usize, new_entry: String) -> Result<(), Box<dyn Error>> {
-        if col_index > self.num_cols || col_index == 0 {
-            return Err(Box::new(CSVError::InvalidColIndex));
-        }
-        let new_col_values: Vec<&str> = new_entry.split(",").collect();
-        let new_col_values_len = new_col_values.len();
-        if new_col_values_len > self.num_rows {
+    fn modify_column(
+        &mut self,
+        column: impl Into<ColumnSelector>,
+        new_entry: String,
+    ) -> Result<(), Box<dyn Error>> {
+        let col_index = self.column_index(column)?;
+        let new_col_values = parse_input(&new_entry);
+        if new_col_values.len() > self.num_rows() {
             return Err(Box::new(CSVError::MoreEntriesThanRequired));
         }
-        if new_col_values_len < self.num_rows {
+        if new_col_values.len() < self.num_rows() {
             return Err(Box::new(CSVError::LessEntriesThanRequired));
         }
 
-        println!(
-            ">>>  \x1b[35mAre you sure you want to modify the column #{}:\x1b[0m",
-            col_index
-        );
-        self.display_column(col_index)?;
-
-        print!(">>  \x1b[35m[y/n]:\x1b[0m ");
-        let _ = io::stdout().flush();
-        let mut input = String::new();
-        let _ = io::stdin().read_line(&mut input);
-        if input != "y\n".to_owned() {
-            println!(">>>  \x1b[33mDid Not Modify Column\x1b[0m");
+        let prompt = format!("Are you sure you want to modify the column #{}:", col_index);
+        if !self.confirm.confirm(&prompt, &|| {
+            let _ = self.display_column(col_index);
+        }) {
+            status!(self, ">>>  \x1b[33mDid Not Modify Column\x1b[0m");
             return Ok(());
         }
 
-        for row_ind in 0..self.num_rows {
-            let row = self.file_data[row_ind].clone();
-            let mut row_vec: Vec<&str> = row.split(",").collect();
-            row_vec[col_index - 1] = new_col_values[row_ind];
-            self.file_data[row_ind] = row_vec.join(",");
+        for (row, cell) in new_col_values.into_iter().enumerate() {
+            self.replace_cell(row, col_index - 1, cell);
         }
+        self.history
+            .commit(format!("modify col {}", self.header[col_index - 1]));
 
-        println!(
+        status!(
+            self,
             ">>>  \x1b[32mSuccessfully Modified Column #{}:\x1b[0m",
             col_index
         );
-        self.display_column(col_index)?;
+        self.render_column(&mut self.status_output.writer(), col_index)?;
 
         Ok(())
     }
@@ -452,7 +652,7 @@ impl FileDataUtils for CSVFile {
     /// # Arguments
     ///
     /// * `row_index` - The index of the row containing the entry to be updated.
-    /// * `col_index` - The index of the column containing the entry to be updated.
+    /// * `column` - The index or title of the column containing the entry to be updated.
     /// * `new_entry` - A string representing the new value for the specified entry.
     ///
     /// # Errors
@@ -462,37 +662,29 @@ impl FileDataUtils for CSVFile {
     fn update_entry(
         &mut self,
         row_index: usize,
-        col_index: usize,
+        column: impl Into<ColumnSelector>,
         new_entry: String,
     ) -> Result<(), Box<dyn Error>> {
-        if row_index > self.num_rows || row_index == 0 {
+        if row_index > self.num_rows() || row_index == 0 {
             return Err(Box::new(CSVError::InvalidRowIndex));
         }
-        if col_index > self.num_cols || col_index == 0 {
-            return Err(Box::new(CSVError::InvalidColIndex));
-        }
-        let row = self.file_data[row_index - 1].clone();
-        let mut row_vec = row.split(",").collect::<Vec<&str>>();
-        let entry = row_vec.remove(col_index - 1);
+        let col_index = self.column_index(column)?;
+        let entry = &self.rows[row_index - 1][col_index - 1];
 
-        println!(
-            ">>>  \x1b[35mAre you sure you want to update the entry: {}\x1b[0m",
-            entry
-        );
-        print!(">>  \x1b[35m[y/n]:\x1b[0m ");
-        let _ = io::stdout().flush();
-        let mut input = String::new();
-        let _ = io::stdin().read_line(&mut input);
-        if input != "y\n".to_owned() {
-            println!(">>>  \x1b[33mDid Not Update Entry\x1b[0m");
+        let prompt = format!("Are you sure you want to update the entry: {}", entry);
+        if !self.confirm.confirm(&prompt, &|| {}) {
+            status!(self, ">>>  \x1b[33mDid Not Update Entry\x1b[0m");
             return Ok(());
         }
 
-        row_vec.insert(col_index - 1, &new_entry);
-        self.file_data[row_index - 1] = row_vec.join(",");
-        println!(
+        let entry = self.replace_cell(row_index - 1, col_index - 1, Cell::parse(&new_entry));
+        self.history
+            .commit(format!("modify item {} {}", row_index, col_index));
+        status!(
+            self,
             ">>>  \x1b[32mSuccessfully Updated Entry: {}, With New Entry: {}\x1b[0m",
-            entry, new_entry
+            entry,
+            new_entry
         );
 
         Ok(())
@@ -508,12 +700,70 @@ impl FileDataUtils for CSVFile {
     ///
     fn sorted_display(&self) -> Result<(), Box<dyn Error>> {
         let mut temp_csv_struct = self.clone();
-        temp_csv_struct.file_data.sort();
-        println!(">>>  \x1b[32mSorted Rows:\x1b[0m");
+        temp_csv_struct.rows.sort();
+        status!(self, ">>>  \x1b[32mSorted Rows:\x1b[0m");
+        temp_csv_struct.display_file(None, None)?;
+        Ok(())
+    }
+
+    /// Displays the CSV file with rows sorted by one or more columns, leaving the file as is.
+    ///
+    /// Numbers are compared numerically, dates chronologically and anything else as text.
+    /// Empty entries come last.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - The columns to sort by, such as `price desc, name asc nocase` (see [`SortKey`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a key is malformed or refers to an unknown column.
+    ///
+    fn display_sorted_by(&self, keys: &str) -> Result<(), Box<dyn Error>> {
+        let order = self.sorted_row_order(&SortKey::parse_list(keys)?)?;
+        let mut temp_csv_struct = self.clone();
+        temp_csv_struct.permute_rows(&order);
+        status!(self, ">>>  \x1b[32mSorted Rows:\x1b[0m");
         temp_csv_struct.display_file(None, None)?;
         Ok(())
     }
 
+    /// Reorders the rows of the CSV file by one or more columns, as shown by
+    /// [`display_sorted_by`](FileDataUtils::display_sorted_by).
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - The columns to sort by, such as `price desc, name asc nocase` (see [`SortKey`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a key is malformed or refers to an unknown column.
+    ///
+    fn sort_by(&mut self, keys: &str) -> Result<(), Box<dyn Error>> {
+        let order = self.sorted_row_order(&SortKey::parse_list(keys)?)?;
+        if order
+            .iter()
+            .enumerate()
+            .all(|(row, &row_index)| row + 1 == row_index)
+        {
+            status!(self, ">>>  \x1b[33mRows Already Sorted\x1b[0m");
+            return Ok(());
+        }
+
+        let prompt = format!("Are you sure you want to sort the rows by {}:", keys.trim());
+        if !self.confirm.confirm(&prompt, &|| {
+            let _ = self.render_rows(&mut io::stdout().lock(), &order);
+        }) {
+            status!(self, ">>>  \x1b[33mDid Not Sort Rows\x1b[0m");
+            return Ok(());
+        }
+
+        self.permute_rows(&order);
+        self.history.commit(format!("sort by {}", keys.trim()));
+        status!(self, ">>>  \x1b[32mSuccessfully Sorted Rows\x1b[0m");
+        Ok(())
+    }
+
     /// Merges the content of another CSV file into the current loaded CSV file.
     ///
     /// This function appends the rows from the specified CSV file (`other`) to the current CSV file.
@@ -528,14 +778,199 @@ impl FileDataUtils for CSVFile {
     /// Returns an error if the two files have incompatible dimensions or if there is any issue with the merge.
     ///
     fn merge_files(&mut self, other: &Self) -> Result<(), Box<dyn Error>> {
-        if self.num_cols != other.num_cols {
+        self.merge_files_with_options(other, &MergeOptions::default())
+    }
+
+    /// Merges the content of another CSV file into the current loaded CSV file, matching
+    /// columns by position or by title (see [`MergeOptions`]).
+    ///
+    /// When merging by title, the other file's columns are reordered to match the loaded file
+    /// and columns it lacks are filled with `_`. Columns only the other file has are added when
+    /// [`MergeOptions::add_columns`] is set, with `_` in the existing rows, and skipped otherwise.
+    /// With [`MergeOptions::source_column`], each row records the path of the file it came from.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - A reference to another CSV file to be merged into the current file.
+    /// * `options` - How to match the columns of the two files.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if merging by position and the two files have a different number of columns.
+    ///
+    fn merge_files_with_options(
+        &mut self,
+        other: &Self,
+        options: &MergeOptions,
+    ) -> Result<(), Box<dyn Error>> {
+        self.merge_many_files(std::slice::from_ref(other), options)
+    }
+
+    /// Merges the content of several CSV files into the current loaded CSV file, in order, as
+    /// [`FileDataUtils::merge_files_with_options`] does for one.
+    ///
+    /// All files are checked before any is merged, so an error leaves the loaded file as it
+    /// was, and the whole merge is undone in one step.
+    ///
+    /// # Arguments
+    ///
+    /// * `others` - The CSV files to be merged into the current file.
+    /// * `options` - How to match the columns of the files.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if merging by position and one of the files has a different number of
+    /// columns than the loaded one.
+    ///
+    fn merge_many_files(
+        &mut self,
+        others: &[Self],
+        options: &MergeOptions,
+    ) -> Result<(), Box<dyn Error>> {
+        if others.is_empty() {
+            return Ok(());
+        }
+        let source_col = options.source_column.as_ref().map(|title| {
+            self.header
+                .iter()
+                .position(|column| column == title)
+                .unwrap_or(self.num_cols())
+        });
+        let data_titles: Vec<String> = (0..self.num_cols())
+            .filter(|&col| Some(col) != source_col)
+            .map(|col| self.header[col].clone())
+            .collect();
+        if !options.by_name
+            && others
+                .iter()
+                .any(|other| other.num_cols() != data_titles.len())
+        {
             return Err(Box::new(CSVError::InvalidDimensions));
         }
-        for row in other.file_data.iter() {
-            self.file_data.push(row.clone());
+        // Positional merges of files with the same cols in another order misalign every row.
+        let mut sorted_titles = data_titles.clone();
+        sorted_titles.sort();
+        let reordered = !options.by_name
+            && others.iter().any(|other| {
+                let mut other_titles = other.header.clone();
+                other_titles.sort();
+                other.header != data_titles && other_titles == sorted_titles
+            });
+        if let (Some(title), Some(col)) = (&options.source_column, source_col) {
+            if col == self.num_cols() {
+                let cells = vec![Cell::from(self.file_path.as_str()); self.num_rows()];
+                self.insert_column(col, title.clone(), cells);
+            }
+        }
+
+        let mut skipped: Vec<&str> = Vec::new();
+        for other in others.iter() {
+            if options.by_name {
+                for title in other.header.iter() {
+                    if self.header.contains(title) || skipped.contains(&title.as_str()) {
+                        continue;
+                    }
+                    if options.add_columns {
+                        let cells = vec![Cell::Null; self.num_rows()];
+                        self.insert_column(self.num_cols(), title.clone(), cells);
+                    } else {
+                        skipped.push(title.as_str());
+                    }
+                }
+            }
+            let mut other_cols = 0..other.num_cols();
+            let sources: Vec<Option<usize>> = (0..self.num_cols())
+                .map(|col| {
+                    if Some(col) == source_col {
+                        None
+                    } else if options.by_name {
+                        other
+                            .header
+                            .iter()
+                            .position(|title| *title == self.header[col])
+                    } else {
+                        other_cols.next()
+                    }
+                })
+                .collect();
+            for row in other.rows.iter() {
+                let cells = sources
+                    .iter()
+                    .enumerate()
+                    .map(|(col, source)| match source {
+                        Some(other_col) => row[*other_col].clone(),
+                        None if Some(col) == source_col => Cell::from(other.file_path.as_str()),
+                        None => Cell::Null,
+                    })
+                    .collect();
+                self.insert_row(self.num_rows(), cells);
+            }
+        }
+        let paths: Vec<&str> = others
+            .iter()
+            .map(|other| other.file_path.as_str())
+            .collect();
+        self.history.commit(format!("merge {}", paths.join(", ")));
+
+        status!(self, ">>>  \x1b[32mSuccessfully Merged Files\x1b[0m");
+        if !skipped.is_empty() {
+            status!(
+                self,
+                ">>>  \x1b[33mSkipped Cols Not In Loaded File: {}\x1b[0m",
+                skipped.join(", ")
+            );
+        }
+        if reordered {
+            status!(
+                self,
+                ">>>  \x1b[33mCols Of Merged File Are In A Different Order. Use 'by name' Or --by-name To Match Them By Title\x1b[0m"
+            );
+        }
+        Ok(())
+    }
+
+    /// Replaces the loaded table with its join with another CSV file (see [`CSVFile::join`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The CSV file to join with the loaded one.
+    /// * `kind` - Which unmatched rows to keep.
+    /// * `on` - The pairs of columns whose entries must match.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no key is given or a key column does not exist in its file.
+    ///
+    fn join_files(
+        &mut self,
+        other: &Self,
+        kind: JoinKind,
+        on: &[JoinKey],
+    ) -> Result<(), Box<dyn Error>> {
+        let joined = self.join(other, kind, on)?;
+
+        let prompt = format!(
+            "Are you sure you want to replace the loaded table with the {} join of {} rows:",
+            kind,
+            joined.num_rows()
+        );
+        if !self.confirm.confirm(&prompt, &|| {
+            if joined.num_rows() > 0 {
+                let _ = joined.render_file(&mut io::stdout().lock(), None, None);
+            }
+        }) {
+            status!(self, ">>>  \x1b[33mDid Not Join Files\x1b[0m");
+            return Ok(());
         }
-        self.num_rows += other.num_rows;
-        println!(">>>  \x1b[32mSuccessfully Merged Files\x1b[0m");
+
+        self.replace_table(joined.header, joined.rows);
+        self.history
+            .commit(format!("{} join {}", kind, other.file_path));
+        status!(
+            self,
+            ">>>  \x1b[32mSuccessfully Joined Files Into {} Rows\x1b[0m",
+            self.num_rows()
+        );
         Ok(())
     }
 
@@ -553,30 +988,27 @@ impl FileDataUtils for CSVFile {
     /// number of columns in the CSV file.
     ///
     fn add_row(&mut self, row_data: String) -> Result<(), Box<dyn Error>> {
-        let row_entries_count = row_data.split(',').count();
-        if row_entries_count > self.num_cols {
+        let new_row = parse_input(&row_data);
+        if new_row.len() > self.num_cols() {
             return Err(Box::new(CSVError::MoreEntriesThanRequired));
         }
-        if row_entries_count < self.num_cols {
+        if new_row.len() < self.num_cols() {
             return Err(Box::new(CSVError::LessEntriesThanRequired));
         }
 
-        println!(
-            ">>>  \x1b[35mAre you sure you want to add this row: {}\x1b[0m",
-            row_data
-        );
-        print!(">>  \x1b[35m[y/n]:\x1b[0m ");
-        let _ = io::stdout().flush();
-        let mut input = String::new();
-        let _ = io::stdin().read_line(&mut input);
-        if input != "y\n".to_owned() {
-            println!(">>>  \x1b[33mDid Not Add Row\x1b[0m");
+        let prompt = format!("Are you sure you want to add this row: {}", row_data);
+        if !self.confirm.confirm(&prompt, &|| {}) {
+            status!(self, ">>>  \x1b[33mDid Not Add Row\x1b[0m");
             return Ok(());
         }
-        self.file_data.push(row_data.clone());
-        self.num_rows += 1;
+        self.insert_row(self.num_rows(), new_row);
+        self.history.commit(format!("add row {}", row_data));
 
-        println!(">>>  \x1b[32mSuccessfully Added Row: {}\x1b[0m", row_data);
+        status!(
+            self,
+            ">>>  \x1b[32mSuccessfully Added Row: {}\x1b[0m",
+            row_data
+        );
         Ok(())
     }
 
@@ -592,38 +1024,30 @@ impl FileDataUtils for CSVFile {
     /// (total number of rows + 1) in the CSV file.
     ///
     fn add_column(&mut self, new_entry: String) -> Result<(), Box<dyn Error>> {
-        let new_col_values: Vec<&str> = new_entry.split(",").collect();
-        let new_col_values_len = new_col_values.len();
-        if new_col_values_len > self.num_rows + 1 {
+        let mut new_col_values = parse_input(&new_entry);
+        if new_col_values.len() > self.num_rows() + 1 {
             return Err(Box::new(CSVError::MoreEntriesThanRequired));
         }
-        if new_col_values_len < self.num_rows + 1 {
+        if new_col_values.len() < self.num_rows() + 1 {
             return Err(Box::new(CSVError::LessEntriesThanRequired));
         }
 
-        println!(">>>  \x1b[35mAre you sure you want to add the column\x1b[0m");
-
-        print!(">> \x1b[35m[y/n]:\x1b[0m ");
-        let _ = io::stdout().flush();
-        let mut input = String::new();
-        let _ = io::stdin().read_line(&mut input);
-        if input != "y\n".to_owned() {
-            println!(">>>  \x1b[33mDid Not Add Column\x1b[0m");
+        let prompt = "Are you sure you want to add the column";
+        if !self.confirm.confirm(prompt, &|| {}) {
+            status!(self, ">>>  \x1b[33mDid Not Add Column\x1b[0m");
             return Ok(());
         }
 
-        self.header.push(',');
-        self.header += new_col_values[0];
-        for row_ind in 0..self.num_rows {
-            self.file_data[row_ind].push(',');
-            self.file_data[row_ind] += new_col_values[row_ind + 1];
-        }
+        let title = new_col_values.remove(0).to_string();
+        let description = format!("add col {}", title);
+        self.insert_column(self.num_cols(), title, new_col_values);
+        self.history.commit(description);
 
-        println!(
+        status!(
+            self,
             ">>>  \x1b[32mSuccessfully Added Column: {}\x1b[0m",
             new_entry
         );
-        self.num_cols += 1;
         Ok(())
     }
 
@@ -631,52 +1055,15 @@ impl FileDataUtils for CSVFile {
     ///
     /// # Arguments
     ///
-    /// * `col_index` - The index of the column to be displayed.
+    /// * `column` - The index or title of the column to be displayed.
     ///
     /// # Errors
     ///
     /// Returns an error if the column index is invalid (greater than the total number of columns
     /// or less than 1).
     ///
-    fn display_column(&self, col_index: usize) -> Result<(), Box<dyn Error>> {
-        if col_index > self.num_cols || col_index == 0 {
-            return Err(Box::new(CSVError::InvalidColIndex));
-        }
-
-        let max_col_length = self.max_col_lengths[col_index - 1];
-        print!("+");
-        print!("{:-<width$}+", "", width = max_col_length + 2);
-        println!();
-
-        print!("|");
-        let header = self.header.split(",").collect::<Vec<&str>>()[col_index - 1];
-        print!(
-            " \x1b[36m{:<width$} \x1b[0m|",
-            header,
-            width = max_col_length
-        );
-        println!();
-
-        print!("+");
-        print!("{:-<width$}+", "", width = max_col_length + 2); // +2 for padding
-        println!();
-
-        for row_ind in 0..self.num_rows {
-            let row_items: Vec<&str> = self.file_data[row_ind].split(',').collect();
-            print!("|");
-            print!(
-                " {:<width$} |  ({})",
-                row_items[col_index - 1],
-                row_ind + 1,
-                width = max_col_length
-            );
-            println!();
-
-            print!("+");
-            print!("{:-<width$}+", "", width = max_col_length + 2); // +2 for padding
-            println!();
-        }
-        Ok(())
+    fn display_column(&self, column: impl Into<ColumnSelector>) -> Result<(), Box<dyn Error>> {
+        self.render_column(&mut io::stdout().lock(), column)
     }
 
     /// Displays the specified row in the CSV file.
@@ -694,87 +1081,1123 @@ impl FileDataUtils for CSVFile {
         self.display_file(Some(row_index), Some(row_index))?;
         Ok(())
     }
-}
-
-impl CSVFile {
-    fn display_horizontal_line(&self) {
-        print!("+");
-        for max_length in &self.max_col_lengths {
-            print!("{:-<width$}+", "", width = max_length + 2);
-        }
-        println!();
-    }
-
-    pub fn new(file_path: &String) -> Self {
-        let path = Path::new(&file_path);
-        if let Some(ext) = path.extension() {
-            if ext != "csv" {
-                eprintln!(
-                    "\x1b[31mcsvr: error loading file: supplied file is not a csv file\x1b[0m"
-                );
-                process::exit(1);
-            }
-        }
-
-        if !path.exists() {
-            eprintln!("\x1b[31mcsvr: error loading file: file doesn't exist\x1b[0m");
-            process::exit(1);
-        }
 
-        let file_read_result = fs::read_to_string(file_path);
-        let file_data: Vec<String> = file_read_result
-            .unwrap()
-            .split("\n")
+    /// Displays the title, inferred type (see [`ColumnType`]) and number of empty or `_`
+    /// entries of every column.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing the table fails.
+    ///
+    fn display_schema(&self) -> Result<(), Box<dyn Error>> {
+        let rows = self
+            .schema()
             .into_iter()
-            .map(|row| row.to_owned())
-            .collect();
-
-        if file_data[0] == "" {
-            eprintln!("\x1b[31mcsvr: error loading file: file is empty\x1b[0m");
-            process::exit(1);
-        }
-        let header = file_data[0].clone();
-        let num_rows = file_data.len() - 1; // row - 1 because we are not including the header
-        let num_cols = file_data[0].split(",").collect::<Vec<&str>>().len();
-        let file_path = file_path.clone();
-
-        let headers: Vec<&str> = header.split(',').collect();
-        let max_col_lengths: Vec<usize> = (0..headers.len())
-            .map(|col| {
-                file_data
-                    .iter()
-                    .map(|row| row.split(',').nth(col).unwrap_or("").len())
-                    .chain(std::iter::once(headers[col].len()))
-                    .max()
-                    .unwrap_or(0)
+            .enumerate()
+            .map(|(col, column)| {
+                let items = vec![
+                    column.name,
+                    column.column_type.to_string(),
+                    column.nulls.to_string(),
+                ];
+                (col + 1, items)
             })
             .collect();
-
-        CSVFile {
-            file_data: file_data[1..].to_vec(),
-            header,
-            num_rows,
-            num_cols,
-            file_path,
-            max_col_lengths,
-        }
+        let table = Table::new(
+            vec!["Column".to_owned(), "Type".to_owned(), "Nulls".to_owned()],
+            rows,
+        );
+        self.renderer.render(&table, &mut io::stdout().lock())?;
+        Ok(())
     }
 
-    pub fn write_to_file(&self) {
-        if self.num_cols == 0 {
-            eprintln!("\x1b[31mcsvr: no data found to write to file\x1b[0m");
-        }
-        let file_path_split: Vec<&str> = self.file_path.split('.').collect();
-        if file_path_split[file_path_split.len() - 1] != "csv" {
-            eprintln!("\x1b[31mcsvr: invalid file extension: must be '.csv'\x1b[0m");
-            return;
-        }
-        let mut new_file_content: String = String::new();
-        new_file_content.push_str(&self.header);
-        for row in self.file_data.iter() {
-            let row_to_push = "\n".to_owned() + row;
-            new_file_content.push_str(&row_to_push);
-        }
-        let _ = fs::write(&self.file_path, new_file_content);
+    /// Displays the statistics of the specified column (see [`ColumnStats`]), one per line.
+    ///
+    /// # Arguments
+    ///
+    /// * `column` - The index or title of the column to be profiled.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the provided column index is invalid.
+    ///
+    fn display_column_stats(
+        &self,
+        column: impl Into<ColumnSelector>,
+    ) -> Result<(), Box<dyn Error>> {
+        let stats = self.column_stats(column)?;
+        let rows = stats
+            .fields()
+            .into_iter()
+            .enumerate()
+            .map(|(line, (title, value))| (line + 1, vec![title.to_owned(), value]))
+            .collect();
+        let table = Table::new(vec!["Statistic".to_owned(), "Value".to_owned()], rows);
+        self.renderer.render(&table, &mut io::stdout().lock())?;
+        Ok(())
+    }
+
+    /// Displays the statistics of every column (see [`ColumnStats`]), one column per line.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing the table fails.
+    ///
+    fn display_stats(&self) -> Result<(), Box<dyn Error>> {
+        let stats = self.stats();
+        let header = match stats.first() {
+            Some(column) => column
+                .fields()
+                .into_iter()
+                .map(|(title, _)| title.to_owned())
+                .collect(),
+            None => return Ok(()),
+        };
+        let rows = stats
+            .iter()
+            .enumerate()
+            .map(|(col, column)| {
+                let items = column
+                    .fields()
+                    .into_iter()
+                    .map(|(_, value)| value)
+                    .collect();
+                (col + 1, items)
+            })
+            .collect();
+        self.renderer
+            .render(&Table::new(header, rows), &mut io::stdout().lock())?;
+        Ok(())
+    }
+
+    /// Reverts the most recent change to the table, such as a deleted column or a sort. Up to
+    /// [`HISTORY_LIMIT`] changes can be undone, most recent first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no change left to undo.
+    ///
+    fn undo(&mut self) -> Result<(), Box<dyn Error>> {
+        let operation = self.history.pop_done().ok_or(CSVError::NothingToUndo)?;
+        let operation = self.revert(operation);
+        status!(
+            self,
+            ">>>  \x1b[32mSuccessfully Undid: {}\x1b[0m",
+            operation.description
+        );
+        self.history.push_undone(operation);
+        Ok(())
+    }
+
+    /// Applies again the most recently undone change. Undone changes are forgotten once the
+    /// table is changed in any other way.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no undone change left to redo.
+    ///
+    fn redo(&mut self) -> Result<(), Box<dyn Error>> {
+        let operation = self.history.pop_undone().ok_or(CSVError::NothingToRedo)?;
+        let operation = self.revert(operation);
+        status!(
+            self,
+            ">>>  \x1b[32mSuccessfully Redid: {}\x1b[0m",
+            operation.description
+        );
+        self.history.push_done(operation);
+        Ok(())
+    }
+
+    /// Displays the changes made to the table, oldest first, followed by the undone changes
+    /// that can be redone.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing the table fails.
+    ///
+    fn display_history(&self) -> Result<(), Box<dyn Error>> {
+        let done = self.history.done().map(|description| (description, "done"));
+        let undone = self
+            .history
+            .undone()
+            .map(|description| (description, "undone"));
+        let rows: Vec<(usize, Vec<String>)> = done
+            .chain(undone)
+            .enumerate()
+            .map(|(line, (description, state))| {
+                (line + 1, vec![description.to_owned(), state.to_owned()])
+            })
+            .collect();
+        if rows.is_empty() {
+            status!(self, ">>>  \x1b[33mNo Changes Made\x1b[0m");
+            return Ok(());
+        }
+        let table = Table::new(vec!["Change".to_owned(), "State".to_owned()], rows);
+        self.renderer.render(&table, &mut io::stdout().lock())?;
+        Ok(())
+    }
+
+    /// Displays the rows matching a filter expression (see [`Filter`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The expression rows must satisfy, such as `age > 30 and city = "Pune"`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the expression is malformed or refers to an unknown column.
+    ///
+    fn display_where(&self, filter: &str) -> Result<(), Box<dyn Error>> {
+        let rows = self.matching_rows(filter)?;
+        if rows.is_empty() {
+            status!(self, ">>>  \x1b[33mNo Rows Matched\x1b[0m");
+            return Ok(());
+        }
+        self.render_rows(&mut io::stdout().lock(), &rows)
+    }
+
+    /// Deletes the rows matching a filter expression (see [`Filter`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The expression rows must satisfy to be deleted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the expression is malformed or refers to an unknown column.
+    ///
+    fn delete_where(&mut self, filter: &str) -> Result<(), Box<dyn Error>> {
+        let rows = self.matching_rows(filter)?;
+        if rows.is_empty() {
+            status!(self, ">>>  \x1b[33mNo Rows Matched\x1b[0m");
+            return Ok(());
+        }
+
+        let prompt = format!("Are you sure you want to delete these {} rows:", rows.len());
+        if !self.confirm.confirm(&prompt, &|| {
+            let _ = self.render_rows(&mut io::stdout().lock(), &rows);
+        }) {
+            status!(self, ">>>  \x1b[33mDid Not Delete Rows\x1b[0m");
+            return Ok(());
+        }
+
+        for row_index in rows.iter().rev() {
+            self.remove_row(row_index - 1);
+        }
+        self.history
+            .commit(format!("delete where {}", filter.trim()));
+        status!(
+            self,
+            ">>>  \x1b[32mSuccessfully Deleted {} Rows\x1b[0m",
+            rows.len()
+        );
+        Ok(())
+    }
+
+    /// Sets one column of the rows matching a filter expression (see [`Filter`]) to a new value.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The expression rows must satisfy to be modified.
+    /// * `column` - The name of the column to be modified.
+    /// * `new_value` - The new value for the column.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the expression is malformed or a column name is unknown.
+    ///
+    fn modify_where(
+        &mut self,
+        filter: &str,
+        column: impl Into<ColumnSelector>,
+        new_value: String,
+    ) -> Result<(), Box<dyn Error>> {
+        let col = self.column_index(column)? - 1;
+        let rows = self.matching_rows(filter)?;
+        if rows.is_empty() {
+            status!(self, ">>>  \x1b[33mNo Rows Matched\x1b[0m");
+            return Ok(());
+        }
+
+        let prompt = format!(
+            "Are you sure you want to set {} to {} in these {} rows:",
+            self.header[col],
+            new_value,
+            rows.len()
+        );
+        if !self.confirm.confirm(&prompt, &|| {
+            let _ = self.render_rows(&mut io::stdout().lock(), &rows);
+        }) {
+            status!(self, ">>>  \x1b[33mDid Not Modify Rows\x1b[0m");
+            return Ok(());
+        }
+
+        for row_index in rows.iter() {
+            self.replace_cell(row_index - 1, col, Cell::parse(&new_value));
+        }
+        self.history.commit(format!(
+            "modify where {} set {} = {}",
+            filter.trim(),
+            self.header[col],
+            new_value
+        ));
+        status!(
+            self,
+            ">>>  \x1b[32mSuccessfully Modified {} Rows Into:\x1b[0m",
+            rows.len()
+        );
+        self.render_rows(&mut self.status_output.writer(), &rows)?;
+        Ok(())
+    }
+}
+
+impl CSVFile {
+    /// Builds a file from a header and rows of cells.
+    ///
+    /// The file has no path and uses the default dialect until they are set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a row does not have exactly one cell per header column.
+    ///
+    pub fn from_rows(header: Vec<String>, rows: Vec<Vec<Cell>>) -> Result<Self, CSVError> {
+        for row in rows.iter() {
+            if row.len() > header.len() {
+                return Err(CSVError::MoreEntriesThanRequired);
+            }
+            if row.len() < header.len() {
+                return Err(CSVError::LessEntriesThanRequired);
+            }
+        }
+        let mut file = CSVFile {
+            header,
+            rows,
+            ..Default::default()
+        };
+        file.widths = ColumnWidths::new(&file.header, &file.rows);
+        Ok(file)
+    }
+
+    /// Returns the column titles.
+    pub fn header(&self) -> &[String] {
+        &self.header
+    }
+
+    /// Returns the number of rows, not counting the header.
+    pub fn num_rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns the number of columns.
+    pub fn num_cols(&self) -> usize {
+        self.header.len()
+    }
+
+    /// Returns all rows, not including the header.
+    pub fn rows(&self) -> &[Vec<Cell>] {
+        &self.rows
+    }
+
+    /// Returns the row at the 1-based `row_index`, if it exists.
+    pub fn row(&self, row_index: usize) -> Option<&[Cell]> {
+        row_index
+            .checked_sub(1)
+            .and_then(|row| self.rows.get(row))
+            .map(|row| row.as_slice())
+    }
+
+    /// Returns the cell at the 1-based `row_index` in `column`, if it exists.
+    pub fn cell(&self, row_index: usize, column: impl Into<ColumnSelector>) -> Option<&Cell> {
+        let col_index = self.column_index(column).ok()?;
+        self.row(row_index)?.get(col_index - 1)
+    }
+
+    /// Returns the 1-based indices of the rows matching a filter expression (see [`Filter`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the expression is malformed or refers to an unknown column.
+    ///
+    pub fn matching_rows(&self, filter: &str) -> Result<Vec<usize>, CSVError> {
+        let filter = Filter::parse(filter)?;
+        let mut positions = Vec::new();
+        for column in filter.columns() {
+            let col = self.column_index(column)? - 1;
+            positions.push((column, col, self.infer_type(col)));
+        }
+        let column_of = |name: &str| {
+            positions
+                .iter()
+                .find(|(column, _, _)| *column == name)
+                .map_or((0, ColumnType::Text), |(_, col, column_type)| {
+                    (*col, *column_type)
+                })
+        };
+        Ok(self
+            .rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| filter.matches(row, &column_of))
+            .map(|(row, _)| row + 1)
+            .collect())
+    }
+
+    /// Returns the 1-based indices of the rows in the order given by `keys`. Rows that compare
+    /// equal on every key keep their current order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no key is given or a key refers to an unknown column.
+    ///
+    pub fn sorted_row_order(&self, keys: &[SortKey]) -> Result<Vec<usize>, CSVError> {
+        if keys.is_empty() {
+            return Err(CSVError::InvalidSortKey(String::new()));
+        }
+        let mut resolved = Vec::new();
+        for key in keys {
+            let col = self.column_index(&key.column)? - 1;
+            resolved.push(ResolvedKey::new(key, col, self.infer_type(col)));
+        }
+        let mut order: Vec<usize> = (0..self.num_rows()).collect();
+        order.sort_by(|&left, &right| {
+            resolved
+                .iter()
+                .map(|key| key.compare(&self.rows[left], &self.rows[right]))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+        Ok(order.into_iter().map(|row| row + 1).collect())
+    }
+
+    /// Joins the rows of this file with the rows of `other` whose `on` columns hold the same
+    /// entries, returning a new file with the settings and path of this one.
+    ///
+    /// The result has the columns of this file followed by the columns of `other` except its key
+    /// columns. Columns of `other` whose title is already taken are prefixed with its file stem,
+    /// as in `orders.date`. Empty and `_` keys never match.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no key is given or a key column does not exist in its file.
+    ///
+    pub fn join(
+        &self,
+        other: &CSVFile,
+        kind: JoinKind,
+        on: &[JoinKey],
+    ) -> Result<CSVFile, CSVError> {
+        if on.is_empty() {
+            return Err(CSVError::InvalidJoinKey(String::new()));
+        }
+        let mut keys = Vec::new();
+        for key in on {
+            keys.push((
+                self.column_index(&key.left)? - 1,
+                other.column_index(&key.right)? - 1,
+            ));
+        }
+        let (header, rows) = join::join(
+            (&self.header, &self.rows),
+            (&other.header, &other.rows),
+            &other.file_path,
+            kind,
+            &keys,
+        );
+        let mut result = self.clone();
+        result.replace_table(header, rows);
+        result.history = History::default();
+        Ok(result)
+    }
+
+    /// Groups the rows by the entries of `keys` and summarises each group with `aggregates`,
+    /// returning a new file with one row per group in order of first appearance.
+    ///
+    /// The result has the key columns followed by one column per aggregate, titled like
+    /// `sum(sales)`, and no file path. Without keys, the whole file is a single group.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a column is unknown, or if `sum` or `avg` is applied to a column that
+    /// is not numeric.
+    ///
+    pub fn group_by(
+        &self,
+        keys: &[ColumnSelector],
+        aggregates: &[Aggregate],
+    ) -> Result<CSVFile, CSVError> {
+        let mut key_cols = Vec::new();
+        for key in keys {
+            key_cols.push(self.column_index(key)? - 1);
+        }
+        let mut header: Vec<String> = key_cols
+            .iter()
+            .map(|&col| self.header[col].clone())
+            .collect();
+        let mut columns = Vec::new();
+        for aggregate in aggregates {
+            let column = match &aggregate.column {
+                Some(column) => {
+                    let col = self.column_index(column)? - 1;
+                    let column_type = self.infer_type(col);
+                    let title = format!("{}({})", aggregate.function, self.header[col]);
+                    if !aggregate.function.accepts(column_type)
+                        && self.rows.iter().any(|row| !is_null(&row[col]))
+                    {
+                        return Err(CSVError::InvalidAggregate(format!(
+                            "{}: {} is a {} col",
+                            title, self.header[col], column_type
+                        )));
+                    }
+                    header.push(title);
+                    Some((col, column_type))
+                }
+                None => {
+                    header.push(format!("{}(*)", aggregate.function));
+                    None
+                }
+            };
+            columns.push((aggregate.function, column));
+        }
+
+        let mut groups: Vec<(Vec<Cell>, Vec<&Vec<Cell>>)> = Vec::new();
+        let mut positions: HashMap<Vec<Cell>, usize> = HashMap::new();
+        for row in self.rows.iter() {
+            let key: Vec<Cell> = key_cols.iter().map(|&col| row[col].clone()).collect();
+            let position = *positions.entry(key.clone()).or_insert_with(|| {
+                groups.push((key, Vec::new()));
+                groups.len() - 1
+            });
+            groups[position].1.push(row);
+        }
+
+        let rows = groups
+            .into_iter()
+            .map(|(mut cells, rows)| {
+                for (function, column) in columns.iter() {
+                    cells.push(match column {
+                        Some((col, column_type)) => {
+                            let entries: Vec<&Cell> = rows.iter().map(|row| &row[*col]).collect();
+                            function.apply(*column_type, &entries)
+                        }
+                        None => Cell::from(rows.len().to_string()),
+                    });
+                }
+                cells
+            })
+            .collect();
+
+        let mut result = CSVFile::from_rows(header, rows)?;
+        result.dialect = self.dialect.clone();
+        result.framing = self.framing;
+        result.encoding = self.encoding;
+        result.status_output = self.status_output;
+        result.backup = self.backup;
+        result.confirm = self.confirm.clone();
+        result.renderer = self.renderer.clone();
+        Ok(result)
+    }
+
+    /// Returns the statistics of `column` (see [`ColumnStats`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the column index is invalid or no column has the given title.
+    ///
+    pub fn column_stats(&self, column: impl Into<ColumnSelector>) -> Result<ColumnStats, CSVError> {
+        let col = self.column_index(column)? - 1;
+        Ok(self.compute_stats(col))
+    }
+
+    /// Returns the statistics of every column (see [`ColumnStats`]).
+    pub fn stats(&self) -> Vec<ColumnStats> {
+        (0..self.num_cols())
+            .map(|col| self.compute_stats(col))
+            .collect()
+    }
+
+    /// Returns the inferred type of `column` (see [`ColumnType::infer`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the column index is invalid or no column has the given title.
+    ///
+    pub fn column_type(&self, column: impl Into<ColumnSelector>) -> Result<ColumnType, CSVError> {
+        let col_index = self.column_index(column)?;
+        Ok(self.infer_type(col_index - 1))
+    }
+
+    /// Returns the title, inferred type and number of nulls of every column.
+    pub fn schema(&self) -> Vec<ColumnSchema> {
+        self.header
+            .iter()
+            .enumerate()
+            .map(|(col, name)| ColumnSchema {
+                name: name.clone(),
+                column_type: self.infer_type(col),
+                nulls: self.rows.iter().filter(|row| is_null(&row[col])).count(),
+            })
+            .collect()
+    }
+
+    /// Returns the 1-based index of `column`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the index is out of range or no column has the given title.
+    ///
+    pub fn column_index(&self, column: impl Into<ColumnSelector>) -> Result<usize, CSVError> {
+        match column.into() {
+            ColumnSelector::Index(col_index) => {
+                if col_index > self.num_cols() || col_index == 0 {
+                    return Err(CSVError::InvalidColIndex);
+                }
+                Ok(col_index)
+            }
+            ColumnSelector::Name(name) => self
+                .header
+                .iter()
+                .position(|title| *title == name)
+                .map(|col| col + 1)
+                .ok_or(CSVError::UnknownColumn(name)),
+        }
+    }
+
+    /// Returns the display width of the widest entry of each column, header included.
+    pub fn max_col_lengths(&self) -> Vec<usize> {
+        self.widths.max()
+    }
+
+    /// Replaces the cell at the 1-based `row_index` in `column` without asking for
+    /// confirmation, returning the previous cell.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the provided row or column index is invalid.
+    ///
+    pub fn set_cell(
+        &mut self,
+        row_index: usize,
+        column: impl Into<ColumnSelector>,
+        value: impl Into<Cell>,
+    ) -> Result<Cell, CSVError> {
+        if row_index > self.num_rows() || row_index == 0 {
+            return Err(CSVError::InvalidRowIndex);
+        }
+        let col_index = self.column_index(column)?;
+        let old = self.replace_cell(row_index - 1, col_index - 1, value.into());
+        self.history
+            .commit(format!("set item {} {}", row_index, col_index));
+        Ok(old)
+    }
+
+    /// Returns the descriptions of the changes that can be undone, oldest first, such as
+    /// `delete col city`.
+    pub fn history(&self) -> Vec<&str> {
+        self.history.done().collect()
+    }
+
+    /// Returns the descriptions of the undone changes that can be redone, in the order
+    /// [`redo`](FileDataUtils::redo) applies them.
+    pub fn redo_history(&self) -> Vec<&str> {
+        self.history.undone().collect()
+    }
+
+    /// Returns `true` if the table has changed since it was loaded or last written with
+    /// [`write_to_file`](CSVFile::write_to_file).
+    pub fn is_modified(&self) -> bool {
+        self.modified
+    }
+
+    /// Returns the options the file was loaded with, for loading other files the same way.
+    pub fn load_options(&self) -> &LoadOptions {
+        &self.load_options
+    }
+
+    /// Forgets every change made so far, so that the current table cannot be undone.
+    pub fn clear_history(&mut self) {
+        self.history = History::default();
+    }
+
+    /// Renders a portion of the CSV file, including headers and specified rows, into `out`
+    /// using the file's [`TableRenderer`].
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The writer receiving the rendered table.
+    /// * `start_index` - The optional starting index for the rendered rows. Default is 1.
+    /// * `end_index` - The optional ending index for the rendered rows. Default is the total number of rows.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the provided indices are invalid or writing to `out` fails.
+    ///
+    pub fn render_file(
+        &self,
+        out: &mut dyn io::Write,
+        start_index: Option<usize>,
+        end_index: Option<usize>,
+    ) -> Result<(), Box<dyn Error>> {
+        let (s_index, e_index) = (
+            start_index.unwrap_or(1),
+            end_index.unwrap_or(self.num_rows()),
+        );
+        if s_index > self.num_rows() || e_index > self.num_rows() || s_index == 0 || e_index == 0 {
+            return Err(Box::new(CSVError::InvalidRowIndex));
+        }
+        if s_index > e_index {
+            return Err(Box::new(CSVError::IncorrectIndices));
+        }
+        let row_indices: Vec<usize> = (s_index..=e_index).collect();
+        self.render_rows(out, &row_indices)
+    }
+
+    /// Renders the header and the rows at the given 1-based indices into `out` using the file's
+    /// [`TableRenderer`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a row index is invalid or writing to `out` fails.
+    ///
+    pub fn render_rows(
+        &self,
+        out: &mut dyn io::Write,
+        row_indices: &[usize],
+    ) -> Result<(), Box<dyn Error>> {
+        if row_indices
+            .iter()
+            .any(|&row_index| row_index > self.num_rows() || row_index == 0)
+        {
+            return Err(Box::new(CSVError::InvalidRowIndex));
+        }
+
+        let table = Table {
+            header: self.header.clone(),
+            rows: row_indices
+                .iter()
+                .map(|&row_index| {
+                    let row_items = self.rows[row_index - 1]
+                        .iter()
+                        .map(|cell| cell.to_string())
+                        .collect();
+                    (row_index, row_items)
+                })
+                .collect(),
+            widths: self.max_col_lengths(),
+        };
+        self.renderer.render(&table, out)?;
+        Ok(())
+    }
+
+    /// Renders the specified column into `out` using the file's [`TableRenderer`].
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The writer receiving the rendered table.
+    /// * `column` - The index or title of the column to be rendered.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the column index is invalid or writing to `out` fails.
+    ///
+    pub fn render_column(
+        &self,
+        out: &mut dyn io::Write,
+        column: impl Into<ColumnSelector>,
+    ) -> Result<(), Box<dyn Error>> {
+        let col_index = self.column_index(column)?;
+
+        let table = Table {
+            header: vec![self.header[col_index - 1].clone()],
+            rows: self
+                .rows
+                .iter()
+                .enumerate()
+                .map(|(row_ind, row)| (row_ind + 1, vec![row[col_index - 1].to_string()]))
+                .collect(),
+            widths: vec![self.max_col_lengths()[col_index - 1]],
+        };
+        self.renderer.render(&table, out)?;
+        Ok(())
+    }
+
+    /// Sets how tables are rendered by the `display` methods.
+    ///
+    /// Files render boxed ASCII tables by default ([`BoxedTableRenderer`]).
+    pub fn set_renderer(&mut self, renderer: impl TableRenderer + Send + Sync + 'static) {
+        self.renderer = Renderer::new(renderer);
+    }
+
+    /// Sets how mutations are confirmed before being applied.
+    ///
+    /// Files approve every mutation by default ([`AlwaysYes`]). Use [`TerminalConfirm`] to
+    /// ask on the terminal, or any other [`Confirm`] implementation.
+    pub fn set_confirm(&mut self, confirm: impl Confirm + Send + Sync + 'static) {
+        self.confirm = Confirmer::new(confirm);
+    }
+
+    /// Loads the file at `file_path`, sniffing its dialect from the contents.
+    ///
+    /// # Errors
+    ///
+    /// See [`CSVFile::open_with_options`].
+    ///
+    pub fn open(file_path: &str) -> Result<Self, CSVError> {
+        CSVFile::open_with_options(file_path, &LoadOptions::default())
+    }
+
+    /// Loads the file at `file_path` using the supplied options.
+    ///
+    /// When `options.dialect` is `None`, the dialect is sniffed from the first records of the
+    /// file (see [`Dialect::detect`]). If sniffing is disabled or inconclusive, the dialect is
+    /// picked from the file extension (see [`Dialect::from_extension`]). The line terminator
+    /// and [`Framing`] are always taken from the file contents, so that writing the file back
+    /// reproduces them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file doesn't exist or cannot be read, is not valid in the
+    /// encoding given in `options`, is empty, has a row with a different number of entries than
+    /// the header, or if no dialect could be determined.
+    ///
+    pub fn open_with_options(file_path: &str, options: &LoadOptions) -> Result<Self, CSVError> {
+        let path = Path::new(file_path);
+        if !path.exists() {
+            return Err(CSVError::FileNotFound);
+        }
+
+        let bytes = fs::read(path).map_err(|err| CSVError::Io(err.to_string()))?;
+        let mut file = CSVFile::from_bytes(bytes, options, Dialect::from_path(file_path))?;
+        file.file_path = file_path.to_owned();
+        Ok(file)
+    }
+
+    /// Loads a file from `reader`, such as standard input, using the supplied options.
+    ///
+    /// The dialect is chosen as in [`CSVFile::open_with_options`], except that the default
+    /// comma-separated dialect is used when sniffing is disabled or inconclusive. The returned
+    /// file has no `file_path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading fails, or if the data is not valid in its encoding, is empty or
+    /// has a row with a different number of entries than the header.
+    ///
+    pub fn from_reader(mut reader: impl Read, options: &LoadOptions) -> Result<Self, CSVError> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .map_err(|err| CSVError::Io(err.to_string()))?;
+        CSVFile::from_bytes(bytes, options, Some(Dialect::default()))
+    }
+
+    fn from_bytes(
+        bytes: Vec<u8>,
+        options: &LoadOptions,
+        fallback: Option<Dialect>,
+    ) -> Result<Self, CSVError> {
+        let encoding = options
+            .encoding
+            .unwrap_or_else(|| TextEncoding::detect(&bytes));
+        let content = encoding.decode(bytes)?;
+        let framing = Framing::detect(&content);
+        let content = content.strip_prefix(BOM).unwrap_or(&content);
+        let detection = match options.dialect {
+            None if options.sniff => Dialect::detect(sniff_sample(content)),
+            _ => None,
+        };
+        let dialect = options
+            .dialect
+            .clone()
+            .or_else(|| detection.as_ref().map(|detected| detected.dialect.clone()))
+            .or(fallback)
+            .ok_or(CSVError::UnknownDialect)?;
+        let dialect = Dialect {
+            line_terminator: LineTerminator::detect(content),
+            ..dialect
+        };
+        let mut records = dialect.split_records(content);
+        // Blank lines at the end of the file hold no record; anywhere else they are rows.
+        while records.last().is_some_and(|record| record.is_empty()) {
+            records.pop();
+        }
+        let mut records = records.into_iter().peekable();
+
+        let header = match records.peek() {
+            None => return Err(CSVError::EmptyFile),
+            Some(first) if options.headerless => (1..=dialect.parse_record(first).len())
+                .map(|col| format!("col{}", col))
+                .collect(),
+            Some(_) => dialect.parse_record(&records.next().unwrap_or_default()),
+        };
+        let mut rows: Vec<Vec<Cell>> = Vec::new();
+        for record in records {
+            let row: Vec<Cell> = dialect
+                .parse_record(&record)
+                .into_iter()
+                .map(Cell::from)
+                .collect();
+            if row.len() != header.len() {
+                return Err(CSVError::RaggedRow(rows.len() + 1, row.len(), header.len()));
+            }
+            rows.push(row);
+        }
+
+        let mut file = CSVFile::from_rows(header, rows)?;
+        file.dialect = dialect;
+        file.framing = framing;
+        file.encoding = encoding;
+        file.headerless = options.headerless;
+        file.detection = detection;
+        file.load_options = options.clone();
+        Ok(file)
+    }
+
+    /// Writes the file to its path in its dialect and marks it as unmodified.
+    ///
+    /// The data goes to a temporary file that then replaces the old one, so a failed write
+    /// leaves the old file as it was. With [`CSVFile::backup`] set, the old file is kept as a
+    /// `.bak` copy next to it (see [`Backup`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is nothing to write, the path has no file extension, or the
+    /// file cannot be written.
+    ///
+    pub fn write_to_file(&mut self) -> Result<(), CSVError> {
+        let file_path = self.file_path.clone();
+        self.write_to_path(&file_path)
+    }
+
+    /// Writes the file to `path` as [`CSVFile::write_to_file`] does. The file is only marked as
+    /// unmodified when `path` is its own path, since a copy elsewhere leaves its changes unsaved.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is nothing to write, the path has no file extension, or the
+    /// file cannot be written.
+    ///
+    pub fn write_to_path(&mut self, path: &str) -> Result<(), CSVError> {
+        if self.num_cols() == 0 {
+            return Err(CSVError::NoDataToWrite);
+        }
+        if Path::new(path).extension().is_none() {
+            return Err(CSVError::MissingExtension);
+        }
+        let new_file_content = self.encoded()?;
+        let backup_path = save::write_atomically(Path::new(path), &new_file_content, self.backup)
+            .map_err(|err| CSVError::WriteFailed(format!("{}: {}", path, err)))?;
+        let is_own_path = path == self.file_path
+            || fs::canonicalize(path).is_ok_and(|written| {
+                fs::canonicalize(&self.file_path).is_ok_and(|own| own == written)
+            });
+        if is_own_path {
+            self.modified = false;
+        }
+
+        status!(
+            self,
+            ">>>  \x1b[32mSuccessfully Wrote File: {}\x1b[0m",
+            path
+        );
+        if let Some(backup_path) = backup_path {
+            status!(
+                self,
+                ">>>  \x1b[32mKept Previous Version As: {}\x1b[0m",
+                backup_path.display()
+            );
+        }
+        Ok(())
+    }
+
+    /// Writes the header and rows to `out` in the file's dialect, framing and encoding.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `out` fails, or if an entry cannot be represented in the
+    /// file's encoding.
+    ///
+    pub fn write_to(&self, out: &mut dyn Write) -> io::Result<()> {
+        let content = self
+            .encoded()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+        out.write_all(&content)?;
+        out.flush()
+    }
+
+    /// Returns the content of the file as written by [`write_to`](CSVFile::write_to).
+    fn encoded(&self) -> Result<Vec<u8>, CSVError> {
+        let line_terminator = self.dialect.line_terminator.as_str();
+        let header = (!self.headerless).then(|| self.dialect.format_record(&self.header));
+        let records: Vec<String> = header
+            .into_iter()
+            .chain(self.rows.iter().map(|row| self.dialect.format_record(row)))
+            .collect();
+        let mut content = String::new();
+        // Encodings that cannot start with a byte order mark drop the one of the loaded file.
+        if self.framing.bom && self.encoding.is_unicode() {
+            content.push(BOM);
+        }
+        content.push_str(&records.join(line_terminator));
+        if self.framing.trailing_newline && !records.is_empty() {
+            content.push_str(line_terminator);
+        }
+        self.encoding.encode(&content)
+    }
+
+    /// Makes the file be written with `LF` line endings, no byte order mark and a line break
+    /// after the last record, whatever it was loaded with.
+    pub fn normalize(&mut self) {
+        self.dialect.line_terminator = LineTerminator::Lf;
+        self.framing = Framing::normalized();
+    }
+
+    fn infer_type(&self, col: usize) -> ColumnType {
+        ColumnType::infer(self.rows.iter().map(|row| &row[col]))
+    }
+
+    fn compute_stats(&self, col: usize) -> ColumnStats {
+        let entries: Vec<&Cell> = self.rows.iter().map(|row| &row[col]).collect();
+        ColumnStats::compute(&self.header[col], self.infer_type(col), &entries)
+    }
+
+    /// Applies an edit recorded in the history through the primitives below.
+    fn apply_edit(&mut self, edit: Edit) {
+        match edit {
+            Edit::ReplaceCell { row, col, cell } => {
+                self.replace_cell(row, col, cell);
+            }
+            Edit::InsertRow { row, cells } => self.insert_row(row, cells),
+            Edit::RemoveRow { row } => {
+                self.remove_row(row);
+            }
+            Edit::InsertColumn { col, title, cells } => self.insert_column(col, title, cells),
+            Edit::RemoveColumn { col } => {
+                self.remove_column(col);
+            }
+            Edit::PermuteRows { order } => self.permute_rows(&order),
+            Edit::ReplaceTable { header, rows } => self.replace_table(header, rows),
+        }
+    }
+
+    /// Reverts `operation` and returns the operation that reverts it in turn, for the other
+    /// history stack.
+    fn revert(&mut self, operation: Operation) -> Operation {
+        for edit in operation.edits.into_iter().rev() {
+            self.apply_edit(edit);
+        }
+        Operation {
+            description: operation.description,
+            edits: self.history.take_pending(),
+        }
+    }
+
+    // All changes to the table go through the primitives below, which take 0-based indices
+    // that have already been validated and keep the column widths in step with the data. Each
+    // one marks the file as modified and records the edit that reverts it in the history, to be
+    // committed as an operation by the caller.
+
+    fn replace_cell(&mut self, row: usize, col: usize, cell: Cell) -> Cell {
+        let old = std::mem::replace(&mut self.rows[row][col], cell);
+        self.widths
+            .replace(col, old.as_str(), self.rows[row][col].as_str());
+        self.modified = true;
+        self.history.record(Edit::ReplaceCell {
+            row,
+            col,
+            cell: old.clone(),
+        });
+        old
+    }
+
+    fn insert_row(&mut self, row: usize, cells: Vec<Cell>) {
+        self.widths.add_row(&cells);
+        self.modified = true;
+        self.history.record(Edit::RemoveRow { row });
+        self.rows.insert(row, cells);
+    }
+
+    fn remove_row(&mut self, row: usize) -> Vec<Cell> {
+        let removed = self.rows.remove(row);
+        self.widths.remove_row(&removed);
+        self.modified = true;
+        self.history.record(Edit::InsertRow {
+            row,
+            cells: removed.clone(),
+        });
+        removed
+    }
+
+    fn insert_column(&mut self, col: usize, title: String, cells: Vec<Cell>) {
+        self.widths.insert_column(col, &title, &cells);
+        self.modified = true;
+        self.history.record(Edit::RemoveColumn { col });
+        self.header.insert(col, title);
+        for (row, cell) in self.rows.iter_mut().zip(cells) {
+            row.insert(col, cell);
+        }
+    }
+
+    /// Reorders the rows so that the row at the 1-based `order[i]` moves to position `i`.
+    fn permute_rows(&mut self, order: &[usize]) {
+        let mut rows: Vec<Option<Vec<Cell>>> = self.rows.drain(..).map(Some).collect();
+        self.rows = order
+            .iter()
+            .map(|&row_index| rows[row_index - 1].take().unwrap_or_default())
+            .collect();
+        self.modified = true;
+        let mut inverse = vec![0; order.len()];
+        for (position, &row_index) in order.iter().enumerate() {
+            inverse[row_index - 1] = position + 1;
+        }
+        self.history.record(Edit::PermuteRows { order: inverse });
+    }
+
+    /// Removes the column at `col` and returns its title.
+    fn remove_column(&mut self, col: usize) -> String {
+        self.widths.remove_column(col);
+        let title = self.header.remove(col);
+        let cells: Vec<Cell> = self.rows.iter_mut().map(|row| row.remove(col)).collect();
+        self.modified = true;
+        self.history.record(Edit::InsertColumn {
+            col,
+            title: title.clone(),
+            cells,
+        });
+        title
+    }
+
+    /// Replaces the whole table, keeping only the old one in the history.
+    fn replace_table(&mut self, header: Vec<String>, rows: Vec<Vec<Cell>>) {
+        self.widths = ColumnWidths::new(&header, &rows);
+        self.modified = true;
+        self.history.record(Edit::ReplaceTable {
+            header: std::mem::replace(&mut self.header, header),
+            rows: std::mem::replace(&mut self.rows, rows),
+        });
+    }
+}
+
+/// Parses values typed by the user, which are always comma-separated.
+fn parse_input(input: &str) -> Vec<Cell> {
+    Dialect::default()
+        .parse_record(input)
+        .into_iter()
+        .map(Cell::from)
+        .collect()
+}
+
+/// Returns the beginning of `content` used for sniffing, cut at the last line break within
+/// [`SNIFF_SAMPLE_BYTES`] so that no partial record is inspected.
+fn sniff_sample(content: &str) -> &str {
+    if content.len() <= SNIFF_SAMPLE_BYTES {
+        return content;
+    }
+    let mut end = SNIFF_SAMPLE_BYTES;
+    while !content.is_char_boundary(end) {
+        end -= 1;
+    }
+    match content[..end].rfind('\n') {
+        Some(index) => &content[..index],
+        None => &content[..end],
     }
 }
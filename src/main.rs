@@ -1,65 +1,235 @@
 use std::{
-    env, fmt,
+    env, fs,
     io::{self, Write},
     process,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
 };
+mod cli;
 mod query_resolver;
 mod test;
-use csvr::CSVFile;
+use cli::{expand_paths, parse_args, CliOptions, FileLoadError};
+use csvr::{CSVFile, FileDataUtils, LoadOptions, StatusOutput, TerminalConfirm, TextEncoding};
 use query_resolver::query_resolver;
 
-#[derive(Debug)]
-enum FileLoadError {
-    FileNameMissing,
-    ExtraArguments,
-}
+/// Exit status when a command run with `-c` or `--script` fails.
+const EXIT_COMMAND_FAILED: i32 = 1;
+/// Exit status when the arguments are invalid or the file or script cannot be loaded.
+const EXIT_USAGE: i32 = 2;
+/// Exit status when the prompt is interrupted with Ctrl-C.
+const EXIT_INTERRUPTED: i32 = 130;
 
-impl fmt::Display for FileLoadError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            FileLoadError::FileNameMissing => write!(f, "csvr: missing argument: filename"),
-            FileLoadError::ExtraArguments => write!(f, "csvr: more arguments than required"),
+fn load_file(options: &CliOptions) -> Result<CSVFile, FileLoadError> {
+    let mut file_names = Vec::new();
+    if options.concat {
+        for pattern in std::iter::once(&options.file_name).chain(&options.concat_files) {
+            if pattern == "-" {
+                file_names.push(pattern.clone());
+            } else {
+                file_names.extend(expand_paths(pattern)?);
+            }
         }
+    } else {
+        file_names.push(options.file_name.clone());
     }
-}
-
-fn load_file(load_file_query: Vec<String>) -> Result<CSVFile, FileLoadError> {
-    if load_file_query.len() < 2 {
-        return Err(FileLoadError::FileNameMissing);
+    let (file_name, concat_files) = file_names.split_first().expect("at least one file name");
+    let load_options = LoadOptions {
+        dialect: options.dialect(),
+        sniff: !options.no_sniff,
+        encoding: options.encoding,
+        headerless: options.no_header,
+    };
+    let mut file = if file_name == "-" {
+        let mut file =
+            CSVFile::from_reader(io::stdin().lock(), &load_options).map_err(FileLoadError::Load)?;
+        file.file_path = file_name.clone();
+        file
+    } else {
+        CSVFile::open_with_options(file_name, &load_options).map_err(FileLoadError::Load)?
+    };
+    file.status_output = StatusOutput::Silent;
+    for concat_file in concat_files {
+        let other =
+            CSVFile::open_with_options(concat_file, &load_options).map_err(FileLoadError::Load)?;
+        file.merge_files_with_options(&other, &options.merge_options())
+            .map_err(|err| FileLoadError::Merge(concat_file.clone(), err.to_string()))?;
+    }
+    file.clear_history();
+    file.status_output = options.status_output();
+    file.backup = options.backup;
+    if options.write_header {
+        file.headerless = false;
+    }
+    let loaded_encoding = file.encoding;
+    if let Some(encoding) = options.write_encoding {
+        file.encoding = encoding;
     }
-    if load_file_query.len() > 2 {
-        return Err(FileLoadError::ExtraArguments);
+    if options.normalize {
+        file.normalize();
+    }
+    if let Some(output) = &options.output {
+        file.file_path = output.clone();
     }
 
-    let file_name = &load_file_query[1];
-    let file = CSVFile::new(file_name);
-    println!(
+    let mut status = file.status_output.writer();
+    let _ = writeln!(
+        status,
         "\x1b[32mSuccessfully Loaded File: \x1b[33m{}\x1b[0m",
-        file_name
+        if file_name == "-" {
+            "<stdin>"
+        } else {
+            file_name
+        }
     );
+    let _ = match &file.detection {
+        Some(detection) => writeln!(
+            status,
+            "\x1b[32mDetected Dialect: \x1b[33m{}, header {}\x1b[0m",
+            file.dialect,
+            if file.headerless {
+                "not read (--no-header)"
+            } else if detection.has_header {
+                "detected"
+            } else {
+                "not detected (first row is still treated as the header, use --no-header to keep it as data)"
+            }
+        ),
+        None => writeln!(status, "\x1b[32mDialect: \x1b[33m{}\x1b[0m", file.dialect),
+    };
+    if file.encoding != loaded_encoding {
+        let _ = writeln!(
+            status,
+            "\x1b[32mEncoding: \x1b[33m{}, written as {}\x1b[0m",
+            loaded_encoding, file.encoding
+        );
+    } else if file.encoding != TextEncoding::Utf8 {
+        let _ = writeln!(status, "\x1b[32mEncoding: \x1b[33m{}\x1b[0m", file.encoding);
+    }
+    if !concat_files.is_empty() {
+        let _ = writeln!(
+            status,
+            "\x1b[32mAppended Files: \x1b[33m{}\x1b[32m, {} Rows In Total\x1b[0m",
+            concat_files.join(", "),
+            file.num_rows()
+        );
+    }
     Ok(file)
 }
 
+/// Reads the commands of a script, skipping blank lines and lines starting with `#`.
+fn read_script(script_path: &str) -> Result<Vec<String>, String> {
+    let script = fs::read_to_string(script_path)
+        .map_err(|err| format!("csvr: could not read script {}: {}", script_path, err))?;
+    Ok(script
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_owned)
+        .collect())
+}
+
+/// Runs `commands` in order without asking for confirmation, stopping at the first failure or
+/// at `exit`. Returns the process exit status.
+fn run_batch(commands: &[String], file: &mut CSVFile) -> i32 {
+    for command in commands {
+        if command.trim() == "exit" {
+            break;
+        }
+        if let Err(err) = query_resolver(command.clone(), file) {
+            eprintln!("\x1b[31m{}\x1b[0m", err);
+            eprintln!("\x1b[31mcsvr: stopped at command: {}\x1b[0m", command);
+            return EXIT_COMMAND_FAILED;
+        }
+    }
+    0
+}
+
+/// Asks whether to quit although the file has unsaved changes. Quits without asking if there are
+/// none, and when stdin is closed since there is no one left to answer.
+fn confirm_quit(file: &CSVFile) -> bool {
+    if !file.is_modified() {
+        return true;
+    }
+    println!(">>>  \x1b[35mThere are unsaved changes. Are you sure you want to quit without writing them\x1b[0m");
+    print!(">>  \x1b[35m[y/n]:\x1b[0m ");
+    let _ = io::stdout().flush();
+    let mut input = String::new();
+    match io::stdin().read_line(&mut input) {
+        Ok(0) | Err(_) => {
+            println!();
+            true
+        }
+        Ok(_) => input.trim() == "y",
+    }
+}
+
+/// Quits on Ctrl-C, unless the file has unsaved changes and Ctrl-C has not been pressed again
+/// since the last command, in which case a warning is shown instead.
+fn handle_interrupts(unsaved: Arc<AtomicBool>, warned: Arc<AtomicBool>) {
+    let result = ctrlc::set_handler(move || {
+        if !unsaved.load(Ordering::SeqCst) || warned.swap(true, Ordering::SeqCst) {
+            println!();
+            process::exit(EXIT_INTERRUPTED);
+        }
+        print!("\n>>>  \x1b[33mThere are unsaved changes. Use 'write' to keep them or press Ctrl-C again to quit without writing them\x1b[0m\n>>>* ");
+        let _ = io::stdout().flush();
+    });
+    if let Err(err) = result {
+        eprintln!("\x1b[31mcsvr: could not handle Ctrl-C: {}\x1b[0m", err);
+    }
+}
+
 fn main() {
-    let load_file_query: Vec<String> = env::args().collect();
-    let load_result = load_file(load_file_query);
-    if let Err(load_file_error) = &load_result {
-        eprintln!("\x1b[31m{}\x1b[0m", load_file_error);
-        process::exit(1);
+    let options = parse_args(env::args().collect()).unwrap_or_else(|err| {
+        eprintln!("\x1b[31m{}\x1b[0m", err);
+        process::exit(EXIT_USAGE);
+    });
+    let mut commands = options.commands.clone();
+    if let Some(script_path) = &options.script {
+        match read_script(script_path) {
+            Ok(script) => commands.extend(script),
+            Err(err) => {
+                eprintln!("\x1b[31m{}\x1b[0m", err);
+                process::exit(EXIT_USAGE);
+            }
+        }
     }
+    let mut file = load_file(&options).unwrap_or_else(|err| {
+        eprintln!("\x1b[31m{}\x1b[0m", err);
+        process::exit(EXIT_USAGE);
+    });
 
-    let mut file = load_result.unwrap();
+    if options.is_batch() {
+        process::exit(run_batch(&commands, &mut file));
+    }
+
+    file.set_confirm(TerminalConfirm);
+    let unsaved = Arc::new(AtomicBool::new(file.is_modified()));
+    let warned = Arc::new(AtomicBool::new(false));
+    handle_interrupts(Arc::clone(&unsaved), Arc::clone(&warned));
 
     loop {
-        print!(">>>  ");
+        // A `*` marks a file with changes that have not been written yet.
+        print!(">>>{} ", if file.is_modified() { "*" } else { " " });
         let _ = io::stdout().flush();
         let mut input = String::new();
-        let _ = io::stdin().read_line(&mut input);
+        let eof = matches!(io::stdin().read_line(&mut input), Ok(0) | Err(_));
+        warned.store(false, Ordering::SeqCst);
 
-        if input == "exit\n".to_owned() {
-            break;
-        } else {
-            query_resolver(input.clone(), &mut file);
+        if eof {
+            println!();
+            if confirm_quit(&file) {
+                break;
+            }
+        } else if input.trim() == "exit" {
+            if confirm_quit(&file) {
+                break;
+            }
+        } else if let Err(err) = query_resolver(input.clone(), &mut file) {
+            eprintln!("\x1b[31m{}\x1b[0m", err);
         }
+        unsaved.store(file.is_modified(), Ordering::SeqCst);
     }
 }
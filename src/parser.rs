@@ -0,0 +1,141 @@
+use crate::Dialect;
+
+impl Dialect {
+    /// Splits raw file content into records following RFC 4180.
+    ///
+    /// Records are separated by `\n` or `\r\n`, except when the line break appears inside a
+    /// quoted field. Quotes follow the same rules as in [`Dialect::parse_record`]: only a quote at
+    /// the start of a field opens a quoted field, so stray quotes such as the inch mark in
+    /// `5" screen` are plain text. Each record is returned as it appears in the source, quotes
+    /// included, ready for [`Dialect::parse_record`]. A trailing line break does not produce an
+    /// extra empty record.
+    ///
+    /// The records are not kept once parsed: on write every row is formatted again with the
+    /// file's dialect (see [`Dialect::format_record`]), so quoting may change even in rows that
+    /// were never edited.
+    pub fn split_records(&self, content: &str) -> Vec<String> {
+        let mut records = Vec::new();
+        let mut current = String::new();
+        let mut in_quotes = false;
+        let mut at_field_start = true;
+        let mut chars = content.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            if self.escape == Some(ch) && self.escape != Some(self.quote) {
+                current.push(ch);
+                if let Some(escaped) = chars.next() {
+                    current.push(escaped);
+                }
+                at_field_start = false;
+                continue;
+            }
+            if in_quotes {
+                if ch == self.quote {
+                    if chars.peek() == Some(&self.quote) {
+                        current.push(ch);
+                        chars.next();
+                    } else {
+                        in_quotes = false;
+                    }
+                }
+                current.push(ch);
+                continue;
+            }
+            match ch {
+                '\r' if chars.peek() == Some(&'\n') => {}
+                '\n' => {
+                    records.push(std::mem::take(&mut current));
+                    at_field_start = true;
+                }
+                _ => {
+                    in_quotes = ch == self.quote && at_field_start;
+                    at_field_start = ch == self.delimiter;
+                    current.push(ch);
+                }
+            }
+        }
+        if !current.is_empty() {
+            records.push(current);
+        }
+        records
+    }
+
+    /// Parses a single record into its fields following RFC 4180.
+    ///
+    /// Quoted fields may contain delimiters, line breaks and doubled quotes (`""`), which are
+    /// unescaped into a single quote. When an escape character is set, it makes the character
+    /// following it literal instead. Stray quotes inside unquoted fields are kept as-is.
+    pub fn parse_record(&self, record: &str) -> Vec<String> {
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut in_quotes = false;
+        let mut at_field_start = true;
+        let mut chars = record.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            if self.escape == Some(ch) && self.escape != Some(self.quote) {
+                if let Some(escaped) = chars.next() {
+                    field.push(escaped);
+                }
+                at_field_start = false;
+                continue;
+            }
+            if in_quotes {
+                if ch == self.quote {
+                    if chars.peek() == Some(&self.quote) {
+                        field.push(ch);
+                        chars.next();
+                    } else {
+                        in_quotes = false;
+                    }
+                } else {
+                    field.push(ch);
+                }
+                continue;
+            }
+            if ch == self.quote && at_field_start {
+                in_quotes = true;
+                at_field_start = false;
+            } else if ch == self.delimiter {
+                fields.push(std::mem::take(&mut field));
+                at_field_start = true;
+            } else {
+                field.push(ch);
+                at_field_start = false;
+            }
+        }
+        fields.push(field);
+        fields
+    }
+
+    /// Encodes a single field, wrapping it in quotes when it contains the delimiter, the quote
+    /// character, the escape character or a line break. Embedded quotes are doubled, or
+    /// prefixed with the escape character when one is set.
+    pub fn quote_field(&self, field: &str) -> String {
+        let special = [self.delimiter, self.quote, '\n', '\r'];
+        let needs_escape = self.escape.is_some_and(|escape| field.contains(escape));
+        if !field.contains(special) && !needs_escape {
+            return field.to_owned();
+        }
+
+        let mut quoted = String::with_capacity(field.len() + 2);
+        quoted.push(self.quote);
+        for ch in field.chars() {
+            if ch == self.quote || self.escape == Some(ch) {
+                quoted.push(self.escape.unwrap_or(self.quote));
+            }
+            quoted.push(ch);
+        }
+        quoted.push(self.quote);
+        quoted
+    }
+
+    /// Encodes a list of fields into a single record, quoting fields where required.
+    pub fn format_record<S: AsRef<str>>(&self, fields: &[S]) -> String {
+        fields
+            .iter()
+            .map(|field| self.quote_field(field.as_ref()))
+            .collect::<Vec<String>>()
+            .join(&self.delimiter.to_string())
+    }
+}
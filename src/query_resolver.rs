@@ -1,399 +1,589 @@
-use ::csvr::{CSVFile, FileDataUtils};
+use crate::cli::expand_paths;
+use ::csvr::{Aggregate, CSVFile, ColumnSelector, FileDataUtils, JoinKey, JoinKind, MergeOptions};
+use std::io;
 
-pub fn query_resolver(query: String, file: &mut CSVFile) {
-    let query_elements: Vec<&str> = query.trim().split(' ').collect();
+/// Runs a single command against the loaded file.
+///
+/// # Errors
+///
+/// Returns the message to show the user if the command is malformed or fails.
+///
+pub fn query_resolver(query: String, file: &mut CSVFile) -> Result<(), String> {
+    let query_elements: Vec<&str> = split_query(&query);
     let num_elements = query_elements.len();
     if num_elements == 0 {
-        return;
+        return Ok(());
     }
     let action = query_elements[0].trim();
     match action {
         "display" | "-d" => {
             if num_elements < 2 {
-                eprintln!(
-                    "\x1b[31mcsvr: argument missing: need either 'row', 'col' or 'file'.\x1b[0m"
+                return Err(
+                    "csvr: argument missing: need either 'row', 'col' or 'file'.".to_owned(),
                 );
-                return;
             }
             match query_elements[1].trim() {
                 "row" => {
                     if num_elements < 3 {
-                        eprintln!("\x1b[31mcsvr: argument missing: need row index.\x1b[0m");
-                        return;
+                        return Err("csvr: argument missing: need row index.".to_owned());
                     }
                     if num_elements > 3 {
-                        eprintln!(
-                            "\x1b[31mcsvr: extra arguments found: need only row index.\x1b[0m"
-                        );
-                        return;
+                        return Err("csvr: extra arguments found: need only row index.".to_owned());
                     }
                     match query_elements[2].trim().parse::<usize>() {
                         Ok(row_ind) => {
-                            let _ = file.display_row(row_ind).unwrap_or_else(|err| {
-                                eprintln!("\x1b[31m{}\x1b[0m", err);
-                            });
-                        }
-                        Err(err) => {
-                            eprintln!("\x1b[31m{}: Not a Valid Integer Index\x1b[0m", err);
+                            file.display_row(row_ind).map_err(|err| err.to_string())?;
                         }
+                        Err(err) => return Err(format!("{}: Not a Valid Integer Index", err)),
                     }
                 }
                 "col" => {
                     if num_elements < 3 {
-                        eprintln!("\x1b[31mcsvr: argument missing: need col index.\x1b[0m");
-                        return;
+                        return Err("csvr: argument missing: need col index or name.".to_owned());
                     }
                     if num_elements > 3 {
-                        eprintln!(
-                            "\x1b[31mcsvr: extra arguments found: need only col index.\x1b[0m"
+                        return Err(
+                            "csvr: extra arguments found: need only col index or name.".to_owned()
                         );
-                        return;
-                    }
-                    match query_elements[2].trim().parse::<usize>() {
-                        Ok(col_ind) => {
-                            let _ = file.display_column(col_ind).unwrap_or_else(|err| {
-                                eprintln!("\x1b[31m{}\x1b[0m", err);
-                            });
-                        }
-                        Err(err) => {
-                            eprintln!("\x1b[31m{}: Not a Valid Integer Index\x1b[0m", err);
-                        }
                     }
+                    file.display_column(ColumnSelector::parse(query_elements[2]))
+                        .map_err(|err| err.to_string())?;
                 }
                 "file" => {
                     if num_elements < 3 {
-                        let _ = file.display_file(None, None).unwrap_or_else(|err| {
-                            eprintln!("\x1b[31m{}\x1b[0m", err);
-                        });
-                        return;
+                        file.display_file(None, None)
+                            .map_err(|err| err.to_string())?;
+                        return Ok(());
                     }
                     if num_elements < 4 {
-                        eprintln!(
-                            "\x1b[31mcsvr: argument missing: need both start and end index.\x1b[0m"
+                        return Err(
+                            "csvr: argument missing: need both start and end index.".to_owned()
                         );
-                        return;
                     }
                     if num_elements > 4 {
-                        eprintln!("\x1b[31mcsvr: extra arguments found: need only start and end index.\x1b[0m");
-                        return;
+                        return Err(
+                            "csvr: extra arguments found: need only start and end index."
+                                .to_owned(),
+                        );
                     }
                     match query_elements[2].trim().parse::<usize>() {
                         Ok(s_index) => match query_elements[3].trim().parse::<usize>() {
                             Ok(e_index) => {
-                                let _ = file
-                                    .display_file(Some(s_index), Some(e_index))
-                                    .unwrap_or_else(|err| {
-                                        eprintln!("\x1b[31m{}\x1b[0m", err);
-                                    });
-                            }
-                            Err(err) => {
-                                eprintln!("\x1b[31m{}: Not a Valid Integer Index\x1b[0m", err);
+                                file.display_file(Some(s_index), Some(e_index))
+                                    .map_err(|err| err.to_string())?;
                             }
+                            Err(err) => return Err(format!("{}: Not a Valid Integer Index", err)),
                         },
-                        Err(err) => {
-                            eprintln!("\x1b[31m{}: Not a Valid Integer Index\x1b[0m", err);
-                        }
+                        Err(err) => return Err(format!("{}: Not a Valid Integer Index", err)),
                     }
                 }
-                _ => eprintln!(
-                    "\x1b[31mcsvr: incorrect argument: need either 'row', 'col' or 'file'.\x1b[0m"
-                ),
+                "where" => {
+                    let filter = rest_of_query(&query, 2);
+                    if filter.is_empty() {
+                        return Err("csvr: argument missing: need filter expression.".to_owned());
+                    }
+                    file.display_where(filter).map_err(|err| err.to_string())?;
+                }
+                _ => {
+                    return Err(
+                        "csvr: incorrect argument: need either 'row', 'col', 'file' or 'where'."
+                            .to_owned(),
+                    )
+                }
             }
         }
 
         "delete" | "-r" => {
             if num_elements < 2 {
-                eprintln!(
-                    "\x1b[31mcsvr: argument missing: need either 'row', 'col' or 'item'.\x1b[0m"
+                return Err(
+                    "csvr: argument missing: need either 'row', 'col' or 'item'.".to_owned(),
                 );
-                return;
             }
             match query_elements[1].trim() {
                 "row" => {
                     if num_elements < 3 {
-                        eprintln!("\x1b[31mcsvr: argument missing: need row index.\x1b[0m");
-                        return;
+                        return Err("csvr: argument missing: need row index.".to_owned());
                     }
                     if num_elements > 3 {
-                        eprintln!(
-                            "\x1b[31mcsvr: extra arguments found: need only row index.\x1b[0m"
-                        );
-                        return;
+                        return Err("csvr: extra arguments found: need only row index.".to_owned());
                     }
                     match query_elements[2].trim().parse::<usize>() {
                         Ok(row_ind) => {
-                            let _ = file.delete_row(row_ind).unwrap_or_else(|err| {
-                                eprintln!("\x1b[31m{}\x1b[0m", err);
-                            });
-                        }
-                        Err(err) => {
-                            eprintln!("\x1b[31m{}: Not a Valid Integer Index\x1b[0m", err);
+                            file.delete_row(row_ind).map_err(|err| err.to_string())?;
                         }
+                        Err(err) => return Err(format!("{}: Not a Valid Integer Index", err)),
                     }
                 }
                 "col" => {
                     if num_elements < 3 {
-                        eprintln!("\x1b[31mcsvr: argument missing: need col index.\x1b[0m");
-                        return;
+                        return Err("csvr: argument missing: need col index or name.".to_owned());
                     }
                     if num_elements > 3 {
-                        eprintln!(
-                            "\x1b[31mcsvr: extra arguments found: need only col index.\x1b[0m"
+                        return Err(
+                            "csvr: extra arguments found: need only col index or name.".to_owned()
                         );
-                        return;
-                    }
-                    match query_elements[2].trim().parse::<usize>() {
-                        Ok(col_ind) => {
-                            let _ = file.delete_column(col_ind).unwrap_or_else(|err| {
-                                eprintln!("\x1b[31m{}\x1b[0m", err);
-                            });
-                        }
-                        Err(err) => {
-                            eprintln!("\x1b[31m{}: Not a Valid Integer Index\x1b[0m", err);
-                        }
                     }
+                    file.delete_column(ColumnSelector::parse(query_elements[2]))
+                        .map_err(|err| err.to_string())?;
                 }
                 "item" => {
                     if num_elements < 4 {
-                        eprintln!(
-                            "\x1b[31mcsvr: argument missing: need both row and col index.\x1b[0m"
+                        return Err(
+                            "csvr: argument missing: need both row index and col index or name."
+                                .to_owned(),
                         );
-                        return;
                     }
                     if num_elements > 4 {
-                        eprintln!("\x1b[31mcsvr: extra arguments found: need only row and col index.\x1b[0m");
-                        return;
+                        return Err(
+                            "csvr: extra arguments found: need only row and col index.".to_owned()
+                        );
                     }
                     match query_elements[2].trim().parse::<usize>() {
-                        Ok(row_ind) => match query_elements[3].trim().parse::<usize>() {
-                            Ok(col_ind) => {
-                                let _ = file.delete_entry(row_ind, col_ind).unwrap_or_else(|err| {
-                                    eprintln!("\x1b[31m{}\x1b[0m", err);
-                                });
-                            }
-                            Err(err) => {
-                                eprintln!("\x1b[31m{}: Not a Valid Integer Index\x1b[0m", err);
-                            }
-                        },
-                        Err(err) => {
-                            eprintln!("\x1b[31m{}: Not a Valid Integer Index\x1b[0m", err);
+                        Ok(row_ind) => {
+                            file.delete_entry(row_ind, ColumnSelector::parse(query_elements[3]))
+                                .map_err(|err| err.to_string())?;
                         }
+                        Err(err) => return Err(format!("{}: Not a Valid Integer Index", err)),
                     }
                 }
-                _ => eprintln!(
-                    "\x1b[31mcsvr: incorrect argument: need either 'row', 'col' or 'item'.\x1b[0m"
-                ),
+                "where" => {
+                    let filter = rest_of_query(&query, 2);
+                    if filter.is_empty() {
+                        return Err("csvr: argument missing: need filter expression.".to_owned());
+                    }
+                    file.delete_where(filter).map_err(|err| err.to_string())?;
+                }
+                _ => {
+                    return Err(
+                        "csvr: incorrect argument: need either 'row', 'col', 'item' or 'where'."
+                            .to_owned(),
+                    )
+                }
             }
         }
 
         "modify" | "-m" => {
             if num_elements < 2 {
-                eprintln!(
-                    "\x1b[31mcsvr: argument missing: need either 'row', 'col' or 'item'.\x1b[0m"
+                return Err(
+                    "csvr: argument missing: need either 'row', 'col' or 'item'.".to_owned(),
                 );
-                return;
             }
             match query_elements[1].trim() {
                 "row" => {
                     if num_elements < 3 {
-                        eprintln!("\x1b[31mcsvr: argument missing: need row index.\x1b[0m");
-                        return;
+                        return Err("csvr: argument missing: need row index.".to_owned());
                     }
                     if num_elements < 4 {
-                        eprintln!("\x1b[31mcsvr: argument missing: need new row values.\x1b[0m");
-                        return;
+                        return Err("csvr: argument missing: need new row values.".to_owned());
                     }
                     if num_elements > 4 {
-                        eprintln!("\x1b[31mcsvr: extra arguments: need only row index and new row values.\x1b[0m");
-                        return;
+                        return Err(
+                            "csvr: extra arguments: need only row index and new row values."
+                                .to_owned(),
+                        );
                     }
                     match query_elements[2].trim().parse::<usize>() {
                         Ok(row_ind) => {
-                            let _ = file
-                                .modify_row(row_ind, query_elements[3].trim().to_owned())
-                                .unwrap_or_else(|err| {
-                                    eprintln!("\x1b[31m{}\x1b[0m", err);
-                                });
-                        }
-                        Err(err) => {
-                            eprintln!("\x1b[31m{}: Not a Valid Integer Index\x1b[0m", err);
+                            file.modify_row(row_ind, query_elements[3].trim().to_owned())
+                                .map_err(|err| err.to_string())?;
                         }
+                        Err(err) => return Err(format!("{}: Not a Valid Integer Index", err)),
                     }
                 }
                 "col" => {
                     if num_elements < 3 {
-                        eprintln!("\x1b[31mcsvr: argument missing: need col index.\x1b[0m");
-                        return;
+                        return Err("csvr: argument missing: need col index or name.".to_owned());
                     }
                     if num_elements < 4 {
-                        eprintln!("\x1b[31mcsvr: argument missing: need new col values.\x1b[0m");
-                        return;
+                        return Err("csvr: argument missing: need new col values.".to_owned());
                     }
                     if num_elements > 4 {
-                        eprintln!("\x1b[31mcsvr: extra arguments: need only col index and new col values.\x1b[0m");
-                        return;
-                    }
-                    match query_elements[2].trim().parse::<usize>() {
-                        Ok(col_ind) => {
-                            let _ = file
-                                .modify_column(col_ind, query_elements[3].trim().to_owned())
-                                .unwrap_or_else(|err| {
-                                    eprintln!("\x1b[31m{}\x1b[0m", err);
-                                });
-                        }
-                        Err(err) => {
-                            eprintln!("\x1b[31m{}: Not a Valid Integer Index\x1b[0m", err);
-                        }
+                        return Err(
+                            "csvr: extra arguments: need only col index and new col values."
+                                .to_owned(),
+                        );
                     }
+                    file.modify_column(
+                        ColumnSelector::parse(query_elements[2]),
+                        query_elements[3].trim().to_owned(),
+                    )
+                    .map_err(|err| err.to_string())?;
                 }
                 "item" => {
                     if num_elements < 3 {
-                        eprintln!("\x1b[31mcsvr: argument missing: need row index.\x1b[0m");
-                        return;
+                        return Err("csvr: argument missing: need row index.".to_owned());
                     }
                     if num_elements < 4 {
-                        eprintln!("\x1b[31mcsvr: argument missing: need col index.\x1b[0m");
-                        return;
+                        return Err("csvr: argument missing: need col index or name.".to_owned());
                     }
                     if num_elements < 5 {
-                        eprintln!("\x1b[31mcsvr: argument missing: need new item value.\x1b[0m");
-                        return;
+                        return Err("csvr: argument missing: need new item value.".to_owned());
                     }
                     if num_elements > 5 {
-                        eprintln!("\x1b[31mcsvr: extra arguments: need only row index, col index and new item value.\x1b[0m");
-                        return;
+                        return Err("csvr: extra arguments: need only row index, col index and new item value.".to_owned());
                     }
                     match query_elements[2].trim().parse::<usize>() {
-                        Ok(row_ind) => match query_elements[3].trim().parse::<usize>() {
-                            Ok(col_ind) => {
-                                let _ = file
-                                    .update_entry(
-                                        row_ind,
-                                        col_ind,
-                                        query_elements[4].trim().to_owned(),
-                                    )
-                                    .unwrap_or_else(|err| {
-                                        eprintln!("\x1b[31m{}\x1b[0m", err);
-                                    });
-                            }
-                            Err(err) => {
-                                eprintln!("\x1b[31m{}: Not a Valid Integer Index\x1b[0m", err);
-                            }
-                        },
-
-                        Err(err) => {
-                            eprintln!("\x1b[31m{}: Not a Valid Integer Index\x1b[0m", err);
+                        Ok(row_ind) => {
+                            file.update_entry(
+                                row_ind,
+                                ColumnSelector::parse(query_elements[3]),
+                                query_elements[4].trim().to_owned(),
+                            )
+                            .map_err(|err| err.to_string())?;
                         }
+                        Err(err) => return Err(format!("{}: Not a Valid Integer Index", err)),
+                    }
+                }
+
+                "where" => {
+                    let (filter, assignment) =
+                        split_keyword(rest_of_query(&query, 2), "set").ok_or_else(|| {
+                            "csvr: argument missing: need 'set column = value' after filter expression."
+                                .to_owned()
+                        })?;
+                    let (column, value) = assignment.split_once('=').ok_or_else(|| {
+                        "csvr: argument missing: need 'column = value' after 'set'.".to_owned()
+                    })?;
+                    file.modify_where(
+                        filter,
+                        ColumnSelector::parse(column),
+                        unquote(value).to_owned(),
+                    )
+                    .map_err(|err| err.to_string())?;
+                }
+                _ => {
+                    return Err(
+                        "csvr: incorrect argument: need either 'row', 'col', 'item' or 'where'."
+                            .to_owned(),
+                    )
+                }
+            }
+        }
+
+        "stats" => {
+            if num_elements < 2 {
+                return Err("csvr: argument missing: need either 'col' or 'file'.".to_owned());
+            }
+            match query_elements[1].trim() {
+                "col" => {
+                    if num_elements < 3 {
+                        return Err("csvr: argument missing: need col index or name.".to_owned());
+                    }
+                    if num_elements > 3 {
+                        return Err(
+                            "csvr: extra arguments found: need only col index or name.".to_owned()
+                        );
                     }
+                    file.display_column_stats(ColumnSelector::parse(query_elements[2]))
+                        .map_err(|err| err.to_string())?;
                 }
+                "file" => {
+                    if num_elements > 2 {
+                        return Err(
+                            "csvr: extra arguments found: 'stats file' takes no arguments."
+                                .to_owned(),
+                        );
+                    }
+                    file.display_stats().map_err(|err| err.to_string())?;
+                }
+                _ => {
+                    return Err("csvr: incorrect argument: need either 'col' or 'file'.".to_owned())
+                }
+            }
+        }
 
-                _ => eprintln!(
-                    "\x1b[31mcsvr: incorrect argument: need either 'row', 'col' or 'item'.\x1b[0m"
-                ),
+        "group" | "-g" => {
+            if num_elements < 2 || query_elements[1].trim() != "by" {
+                return Err("csvr: argument missing: need 'by' followed by group cols.".to_owned());
             }
+            let rest = rest_of_query(&query, 2);
+            let (rest, target) = match split_keyword(rest, "into") {
+                Some((rest, target)) => (rest, Some(unquote(target))),
+                None => (rest, None),
+            };
+            let (keys, aggregates) = split_keyword(rest, "agg").unwrap_or((rest, ""));
+            if keys.is_empty() {
+                return Err("csvr: argument missing: need group cols.".to_owned());
+            }
+            let aggregates = Aggregate::parse_list(aggregates).map_err(|err| err.to_string())?;
+            let mut groups = file
+                .group_by(&ColumnSelector::parse_list(keys), &aggregates)
+                .map_err(|err| err.to_string())?;
+            if groups.num_rows() > 0 {
+                groups
+                    .display_file(None, None)
+                    .map_err(|err| err.to_string())?;
+            }
+            if let Some(target) = target {
+                groups.file_path = target.to_owned();
+                groups.write_to_file().map_err(|err| err.to_string())?;
+            }
+        }
+
+        "filter" | "-f" => {
+            let filter = rest_of_query(&query, 1);
+            if filter.is_empty() {
+                return Err("csvr: argument missing: need filter expression.".to_owned());
+            }
+            file.display_where(filter).map_err(|err| err.to_string())?;
+        }
+
+        "schema" => {
+            if num_elements > 1 {
+                return Err("csvr: extra arguments: command doesn't take any arguments.".to_owned());
+            }
+            file.display_schema().map_err(|err| err.to_string())?;
         }
 
         "add" | "-a" => {
             if num_elements < 2 {
-                eprintln!("\x1b[31mcsvr: argument missing: need either 'row' or 'col'.\x1b[0m");
-                return;
+                return Err("csvr: argument missing: need either 'row' or 'col'.".to_owned());
             }
             match query_elements[1].trim() {
                 "row" => {
                     if num_elements < 3 {
-                        eprintln!("\x1b[31mcsvr: argument missing: need row values.\x1b[0m");
-                        return;
+                        return Err("csvr: argument missing: need row values.".to_owned());
                     }
                     if num_elements > 3 {
-                        eprintln!(
-                            "\x1b[31mcsvr: extra arguments: need only new row values.\x1b[0m"
-                        );
-                        return;
+                        return Err("csvr: extra arguments: need only new row values.".to_owned());
                     }
-                    let _ = file
-                        .add_row(query_elements[2].trim().to_owned())
-                        .unwrap_or_else(|err| {
-                            eprintln!("\x1b[31m{}\x1b[0m", err);
-                        });
+                    file.add_row(query_elements[2].trim().to_owned())
+                        .map_err(|err| err.to_string())?;
                 }
                 "col" => {
                     if num_elements < 3 {
-                        eprintln!("\x1b[31mcsvr: argument missing: need col values.\x1b[0m");
-                        return;
+                        return Err("csvr: argument missing: need col values.".to_owned());
                     }
                     if num_elements > 3 {
-                        eprintln!(
-                            "\x1b[31mcsvr: extra arguments: need only new col values.\x1b[0m"
-                        );
-                        return;
+                        return Err("csvr: extra arguments: need only new col values.".to_owned());
                     }
-                    let _ = file
-                        .add_column(query_elements[2].trim().to_owned())
-                        .unwrap_or_else(|err| {
-                            eprintln!("\x1b[31m{}\x1b[0m", err);
-                        });
+                    file.add_column(query_elements[2].trim().to_owned())
+                        .map_err(|err| err.to_string())?;
                 }
-                _ => eprintln!(
-                    "\x1b[31mcsvr: incorrect argument: need either 'row' or 'col'.\x1b[0m"
-                ),
+                _ => return Err("csvr: incorrect argument: need either 'row' or 'col'.".to_owned()),
             }
         }
 
         "merge" | "-M" => {
             if num_elements < 2 {
-                eprintln!("\x1b[31mcsvr: argument missing: need second file's name.\x1b[0m");
-                return;
+                return Err("csvr: argument missing: need second file's name.".to_owned());
             }
-            if num_elements > 2 {
-                eprintln!("\x1b[31mcsvr: extra arguments: need only second file's name.\x1b[0m");
-                return;
+            let mut options = MergeOptions::default();
+            let mut words = query_elements[2..].iter().map(|word| word.trim());
+            while let Some(word) = words.next() {
+                match word {
+                    "by" if words.next() == Some("name") => options.by_name = true,
+                    "add" if options.by_name => options.add_columns = true,
+                    "source" => match words.next() {
+                        Some(title) => options.source_column = Some(unquote(title).to_owned()),
+                        None => {
+                            return Err("csvr: argument missing: need source col name.".to_owned())
+                        }
+                    },
+                    _ => {
+                        return Err(
+                            "csvr: extra arguments: need only file names, optionally followed by 'by name [add]' and 'source <col>'."
+                                .to_owned(),
+                        )
+                    }
+                }
+            }
+
+            let mut others = Vec::new();
+            for path in expand_paths(unquote(query_elements[1])).map_err(|err| err.to_string())? {
+                if path == file.file_path {
+                    continue;
+                }
+                match CSVFile::open_with_options(&path, file.load_options()) {
+                    Ok(other) => others.push(other),
+                    Err(err) => return Err(format!("csvr: error loading file: {}: {}", path, err)),
+                }
+            }
+            file.merge_many_files(&others, &options)
+                .map_err(|err| match others.as_slice() {
+                    [other] => format!("csvr: error merging file: {}: {}", other.file_path, err),
+                    _ => format!("csvr: error merging files: {}", err),
+                })?;
+        }
+
+        "join" | "-j" => {
+            let (kind, file_word) =
+                match query_elements.get(1).and_then(|word| JoinKind::parse(word)) {
+                    Some(kind) => (kind, 2),
+                    None => (JoinKind::Inner, 1),
+                };
+            if num_elements < file_word + 3 || query_elements[file_word + 1].trim() != "on" {
+                return Err(
+                    "csvr: argument missing: need other file's name followed by 'on' and key cols."
+                        .to_owned(),
+                );
+            }
+            let on = JoinKey::parse_list(rest_of_query(&query, file_word + 2))
+                .map_err(|err| err.to_string())?;
+            match CSVFile::open_with_options(
+                unquote(query_elements[file_word]),
+                file.load_options(),
+            ) {
+                Ok(other) => {
+                    file.join_files(&other, kind, &on)
+                        .map_err(|err| err.to_string())?;
+                }
+                Err(err) => return Err(format!("csvr: error loading file: {}", err)),
             }
-            let other = CSVFile::new(&query_elements[1].trim().to_owned());
-            let _ = file.merge_files(&other).unwrap_or_else(|err| {
-                eprintln!("\x1b[31m{}\x1b[0m", err);
-            });
         }
 
         "sort" | "-s" => {
+            if num_elements == 1 {
+                file.sorted_display().map_err(|err| err.to_string())?;
+                return Ok(());
+            }
+            if query_elements[1].trim() != "by" {
+                return Err("csvr: incorrect argument: need 'by' followed by sort keys.".to_owned());
+            }
+            let keys = rest_of_query(&query, 2);
+            match keys.strip_suffix("apply") {
+                Some(keys) if query_elements[num_elements - 1] == "apply" => {
+                    if keys.trim().is_empty() {
+                        return Err("csvr: argument missing: need sort keys.".to_owned());
+                    }
+                    file.sort_by(keys).map_err(|err| err.to_string())?;
+                }
+                _ => {
+                    if keys.is_empty() {
+                        return Err("csvr: argument missing: need sort keys.".to_owned());
+                    }
+                    file.display_sorted_by(keys)
+                        .map_err(|err| err.to_string())?;
+                }
+            }
+        }
+
+        "undo" | "redo" | "history" => {
             if num_elements > 1 {
-                eprintln!(
-                    "\x1b[31mcsvr: extra arguments: command doesn't take any arguments.\x1b[0m"
-                );
-                return;
+                return Err("csvr: extra arguments: command doesn't take any arguments.".to_owned());
             }
-            let _ = file.sorted_display().unwrap_or_else(|err| {
-                eprintln!("\x1b[31m{}\x1b[0m", err);
-            });
+            match action {
+                "undo" => file.undo(),
+                "redo" => file.redo(),
+                _ => file.display_history(),
+            }
+            .map_err(|err| err.to_string())?;
         }
 
         "write" | "-w" => {
             if num_elements > 2 {
-                eprintln!("\x1b[31mcsvr: extra arguments: need only new file's name.\x1b[0m");
-                return;
+                return Err("csvr: extra arguments: need only new file's name.".to_owned());
             }
-            if num_elements == 2 {
-                let mut new_file = file.clone();
-                new_file.file_path = query_elements[1].trim().to_owned();
-                new_file.clone().write_to_file();
+            let target = if num_elements == 2 {
+                query_elements[1].trim()
+            } else {
+                file.file_path.as_str()
+            };
+            if target == "-" {
+                file.write_to(&mut io::stdout().lock())
+                    .map_err(|err| format!("csvr: could not write to stdout: {}", err))?;
+            } else if num_elements == 2 {
+                let target = target.to_owned();
+                file.write_to_path(&target).map_err(|err| err.to_string())?;
             } else {
-                file.write_to_file();
+                file.write_to_file().map_err(|err| err.to_string())?;
             }
         }
 
         "help" | "-h" => {
             if num_elements > 2 {
-                eprintln!(
-                    "\x1b[31mcsvr: extra arguments: command doesn't take any arguments.\x1b[0m"
-                );
-                return;
+                return Err("csvr: extra arguments: command doesn't take any arguments.".to_owned());
             }
             display_help();
         }
 
-        _ => eprintln!(
-            "\x1b[31mcsvr: command not found: use 'help' or '-h' for available commands.\x1b[0m"
-        ),
+        _ => {
+            return Err(
+                "csvr: command not found: use 'help' or '-h' for available commands.".to_owned(),
+            )
+        }
+    }
+    Ok(())
+}
+
+/// Splits `query` into words at whitespace outside of quotes. Quotes are kept, so that values
+/// such as `"Smith, John",42` still parse as comma-separated entries.
+fn split_query(query: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = None;
+    let mut quote = None;
+    let mut previous = ' ';
+    for (index, ch) in query.char_indices() {
+        match quote {
+            Some(open) if ch == open => quote = None,
+            Some(_) => {}
+            None if ch.is_whitespace() => {
+                if let Some(word_start) = start.take() {
+                    words.push(&query[word_start..index]);
+                }
+            }
+            None => {
+                // Only quotes opening an entry count, so that `O'Brien` stays a plain word.
+                if (ch == '"' || ch == '\'') && (start.is_none() || previous == ',') {
+                    quote = Some(ch);
+                }
+                start.get_or_insert(index);
+            }
+        }
+        previous = ch;
+    }
+    if let Some(word_start) = start {
+        words.push(&query[word_start..]);
+    }
+    words
+}
+
+/// Returns the text of `query` after its first `words` words, keeping the spacing of quoted
+/// values intact.
+fn rest_of_query(query: &str, words: usize) -> &str {
+    let mut rest = query.trim();
+    for _ in 0..words {
+        rest = rest
+            .split_once(' ')
+            .map_or("", |(_, tail)| tail)
+            .trim_start();
+    }
+    rest
+}
+
+/// Splits `text` at the first whole-word `keyword` outside of quotes, such as the `set` of
+/// `filter set assignment`.
+fn split_keyword<'a>(text: &'a str, keyword: &str) -> Option<(&'a str, &'a str)> {
+    let mut quote = None;
+    for (index, ch) in text.char_indices() {
+        match quote {
+            Some(open) if ch == open => quote = None,
+            Some(_) => {}
+            None if ch == '"' || ch == '\'' => quote = Some(ch),
+            None if ch.is_whitespace() => {
+                let tail = &text[index + ch.len_utf8()..];
+                let is_keyword = tail
+                    .get(..keyword.len())
+                    .is_some_and(|word| word.eq_ignore_ascii_case(keyword));
+                if is_keyword && tail[keyword.len()..].starts_with(char::is_whitespace) {
+                    return Some((text[..index].trim(), tail[keyword.len()..].trim()));
+                }
+            }
+            None => {}
+        }
+    }
+    None
+}
+
+/// Trims `value` and removes the quotes around it, if any.
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+    for quote in ['"', '\''] {
+        if value.len() >= 2 && value.starts_with(quote) && value.ends_with(quote) {
+            return &value[1..value.len() - 1];
+        }
     }
+    value
 }
 
 fn display_help() {
@@ -401,20 +591,24 @@ fn display_help() {
             1. \x1b[36mdisplay\x1b[0m | \x1b[36m-d\x1b[0m: used to display the file or parts of the file\n
                 example: \x1b[36mdisplay\x1b[0m \x1b[33mrow\x1b[0m \x1b[35m1\x1b[0m -: displays the first row\n
                          \x1b[36mdisplay\x1b[0m \x1b[33mcol\x1b[0m \x1b[35m1\x1b[0m -: displays the first col\n
+                         \x1b[36mdisplay\x1b[0m \x1b[33mcol\x1b[0m \x1b[35m\"first name\"\x1b[0m -: displays the col titled 'first name'\n
                          \x1b[36mdisplay\x1b[0m \x1b[33mfile\x1b[0m -: displays the whole file\n
                          \x1b[36mdisplay\x1b[0m \x1b[33mfile\x1b[0m \x1b[35m1\x1b[0m \x1b[35m5\x1b[0m -: displays from row 1 to 5\n
+                         \x1b[36mdisplay\x1b[0m \x1b[33mwhere\x1b[0m \x1b[35mage > 30 and city = \"Pune\"\x1b[0m -: displays the rows matching the filter\n
 
 
             2. \x1b[36mdelete\x1b[0m | \x1b[36m-r\x1b[0m: used to delete a row, col or item in the file\n
                 example: \x1b[36mdelete\x1b[0m \x1b[33mrow\x1b[0m \x1b[35m1\x1b[0m -: deletes the first row\n
                          \x1b[36mdelete\x1b[0m \x1b[33mcol\x1b[0m \x1b[35m1\x1b[0m -: deletes the first col\n
                          \x1b[36mdelete\x1b[0m \x1b[33mitem\x1b[0m \x1b[35m1\x1b[0m \x1b[35m5\x1b[0m -: deletes the item in 1st row and 5th col\n
+                         \x1b[36mdelete\x1b[0m \x1b[33mwhere\x1b[0m \x1b[35mstatus in (closed, void)\x1b[0m -: deletes the rows matching the filter\n
 
 
             3. \x1b[36mmodify\x1b[0m | \x1b[36m-m\x1b[0m: used to modify a row, col or item in the file\n
                 example: \x1b[36mmodify\x1b[0m \x1b[33mrow\x1b[0m \x1b[35m1\x1b[0m \x1b[35mthese,are,new,row,values\x1b[0m -: updates the first row with the values supplied\n
                          \x1b[36mmodify\x1b[0m \x1b[33mcol\x1b[0m \x1b[35m1\x1b[0m \x1b[35mthese,are,new,col,values\x1b[0m -: updates the first col with the values supplied\n
                          \x1b[36mmodify\x1b[0m \x1b[33mitem\x1b[0m \x1b[35m1\x1b[0m \x1b[35m5\x1b[0m \x1b[35mnew\x1b[0m -: updates the item in 1st row and 5th col with the value supplied\n
+                         \x1b[36mmodify\x1b[0m \x1b[33mwhere\x1b[0m \x1b[35mcity is empty\x1b[0m \x1b[33mset\x1b[0m \x1b[35mcity = Pune\x1b[0m -: sets the col in the rows matching the filter\n
 
 
             4. \x1b[36madd\x1b[0m | \x1b[36m-a\x1b[0m: used to modify a row or col in the file\n
@@ -424,15 +618,62 @@ fn display_help() {
 
             5. \x1b[36mmerge\x1b[0m | \x1b[36m-M\x1b[0m: used to merge a file with the one currently loaded. header of the loaded file is the new header\n
                 example: \x1b[36mmerge\x1b[0m \x1b[35mfile_name.csv\x1b[0m -: merges the file 'file_name.csv' with the loaded file\n         
+                         \x1b[36mmerge\x1b[0m \x1b[35mfile_name.csv\x1b[0m \x1b[33mby name\x1b[0m -: matches cols by title, filling missing cols with '_'\n
+                         \x1b[36mmerge\x1b[0m \x1b[35mfile_name.csv\x1b[0m \x1b[33mby name add\x1b[0m -: also adds the cols only 'file_name.csv' has\n
+                         \x1b[36mmerge\x1b[0m \x1b[35m\"part-*.csv\"\x1b[0m \x1b[33msource\x1b[0m \x1b[35mfile\x1b[0m -: merges every matching file, recording each row's file in the col 'file'\n
 
 
             6. \x1b[36msort\x1b[0m | \x1b[36m-s\x1b[0m: used to display alphabetically sorted rows of the file\n
                 example: \x1b[36msort\x1b[0m -: sorts and displays the rows of the loaded file\n
+                         \x1b[36msort\x1b[0m \x1b[33mby\x1b[0m \x1b[35mprice desc, name asc nocase\x1b[0m -: displays the rows sorted by price, then by name\n
+                         \x1b[36msort\x1b[0m \x1b[33mby\x1b[0m \x1b[35mprice desc\x1b[0m \x1b[33mapply\x1b[0m -: sorts the rows of the loaded file by price\n
+                numbers sort numerically and dates chronologically. add \x1b[35mnatural\x1b[0m to sort 'file2' before 'file10'\n
 
 
             7. \x1b[36mwrite\x1b[0m | \x1b[36m-w\x1b[0m: used to write the changes to a new file or the loaded file\n
                 example: \x1b[36mwrite\x1b[0m \x1b[35mfile_name.csv\x1b[0m -: writes the changes to the file 'file_name.csv'\n         
+                         \x1b[36mwrite\x1b[0m \x1b[35m-\x1b[0m -: writes the changes to the standard output\n         
                          \x1b[36mwrite\x1b[0m -: writes the changes to the loaded file\n         
+
+
+            cols can be given by index or by title anywhere. quote titles containing spaces or made of digits\n
+
+
+            8. \x1b[36mfilter\x1b[0m | \x1b[36m-f\x1b[0m: used to display the rows matching a filter, same as 'display where'\n
+                filters compare cols by name with =, !=, <, <=, >, >=, in (...), like (% and _ wildcards) and is [not] empty,\n
+                combined with and, or, not and parentheses. quote names and values containing spaces\n
+                example: \x1b[36mfilter\x1b[0m \x1b[35mname like \"A%\" or not (age >= 18)\x1b[0m -: displays the rows matching the filter\n
+
+
+            9. \x1b[36mschema\x1b[0m: used to display the type of each col: integer, float, boolean, date or text\n
+                empty and '_' items are counted as nulls and ignored when inferring the type.\n
+                filters and sort compare items according to the type of their col\n
+                example: \x1b[36mschema\x1b[0m -: displays the cols of the loaded file with their types\n
+
+
+            10. \x1b[36mgroup\x1b[0m | \x1b[36m-g\x1b[0m: used to summarise the rows sharing the same values in one or more cols\n
+                aggregates are count(*), count(col), sum(col), avg(col), min(col) and max(col). nulls are skipped\n
+                example: \x1b[36mgroup\x1b[0m \x1b[33mby\x1b[0m \x1b[35mregion\x1b[0m \x1b[33magg\x1b[0m \x1b[35msum(sales), avg(price), count(*)\x1b[0m -: displays the totals per region\n
+                         \x1b[36mgroup\x1b[0m \x1b[33mby\x1b[0m \x1b[35mregion, year\x1b[0m \x1b[33magg\x1b[0m \x1b[35mcount(*)\x1b[0m \x1b[33minto\x1b[0m \x1b[35mcounts.csv\x1b[0m -: also writes the result to 'counts.csv'\n
+
+
+            11. \x1b[36mstats\x1b[0m: used to profile a col or every col of the file\n
+                shows count, nulls, distinct, min, max, mean, median, std dev and the most frequent values\n
+                example: \x1b[36mstats\x1b[0m \x1b[33mcol\x1b[0m \x1b[35mprice\x1b[0m -: displays the statistics of the col 'price'\n
+                         \x1b[36mstats\x1b[0m \x1b[33mfile\x1b[0m -: displays the statistics of every col\n
+
+
+            12. \x1b[36mjoin\x1b[0m | \x1b[36m-j\x1b[0m: used to replace the loaded file with its join with another file\n
+                kinds are inner (default), left, right and full. cols of the other file with a taken name are prefixed with its name\n
+                example: \x1b[36mjoin\x1b[0m \x1b[35morders.csv\x1b[0m \x1b[33mon\x1b[0m \x1b[35mid\x1b[0m -: keeps the rows whose id is in both files\n
+                         \x1b[36mjoin\x1b[0m \x1b[33mleft\x1b[0m \x1b[35morders.csv\x1b[0m \x1b[33mon\x1b[0m \x1b[35mid = customer_id\x1b[0m -: keeps every row of the loaded file\n
+
+
+            13. \x1b[36mundo\x1b[0m | \x1b[36mredo\x1b[0m | \x1b[36mhistory\x1b[0m: used to revert or reapply the changes made to the loaded file\n
+                every delete, modify, add, merge, join and applied sort can be undone, up to the last 100 changes\n
+                example: \x1b[36mundo\x1b[0m -: reverts the last change\n
+                         \x1b[36mredo\x1b[0m -: reapplies the last undone change. making another change forgets the undone ones\n
+                         \x1b[36mhistory\x1b[0m -: displays the changes made so far and the undone ones\n
     ";
 
     println!("{}", help_string);
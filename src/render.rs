@@ -0,0 +1,130 @@
+use std::{
+    fmt::{Debug, Formatter},
+    io::{self, Write},
+    sync::Arc,
+};
+
+use crate::display_width;
+
+/// A selection of a file's data, ready to be rendered.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Table {
+    /// Column titles.
+    pub header: Vec<String>,
+    /// Rows paired with their 1-based row number in the file.
+    pub rows: Vec<(usize, Vec<String>)>,
+    /// Display width of each column, as measured by [`display_width`].
+    pub widths: Vec<usize>,
+}
+
+impl Table {
+    /// Builds a table, measuring the width of each column from the header and rows.
+    pub fn new(header: Vec<String>, rows: Vec<(usize, Vec<String>)>) -> Self {
+        let mut widths: Vec<usize> = header.iter().map(|title| display_width(title)).collect();
+        for (_, items) in rows.iter() {
+            for (width, item) in widths.iter_mut().zip(items) {
+                *width = (*width).max(display_width(item));
+            }
+        }
+        Table {
+            header,
+            rows,
+            widths,
+        }
+    }
+}
+
+/// Renders a [`Table`] into any writer.
+pub trait TableRenderer {
+    fn render(&self, table: &Table, out: &mut dyn Write) -> io::Result<()>;
+}
+
+/// Renders tables as ASCII boxes, with each row followed by its row number:
+///
+/// ```text
+/// +------+-----+
+/// | name | age |
+/// +------+-----+
+/// | asha | 31  |  (1)
+/// +------+-----+
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct BoxedTableRenderer {
+    /// Whether to highlight the header with ANSI color codes.
+    pub color: bool,
+}
+
+impl Default for BoxedTableRenderer {
+    fn default() -> Self {
+        BoxedTableRenderer { color: true }
+    }
+}
+
+impl BoxedTableRenderer {
+    fn horizontal_line(&self, widths: &[usize], out: &mut dyn Write) -> io::Result<()> {
+        write!(out, "+")?;
+        for width in widths {
+            write!(out, "{:-<width$}+", "", width = width + 2)?;
+        }
+        writeln!(out)
+    }
+}
+
+impl TableRenderer for BoxedTableRenderer {
+    fn render(&self, table: &Table, out: &mut dyn Write) -> io::Result<()> {
+        self.horizontal_line(&table.widths, out)?;
+
+        write!(out, "|")?;
+        for (title, width) in table.header.iter().zip(&table.widths) {
+            if self.color {
+                write!(out, " \x1b[36m{} \x1b[0m|", pad(title, *width))?;
+            } else {
+                write!(out, " {} |", pad(title, *width))?;
+            }
+        }
+        writeln!(out)?;
+        self.horizontal_line(&table.widths, out)?;
+
+        for (row_number, items) in &table.rows {
+            write!(out, "|")?;
+            for (item, width) in items.iter().zip(&table.widths) {
+                write!(out, " {} |", pad(item, *width))?;
+            }
+            writeln!(out, "  ({})", row_number)?;
+            self.horizontal_line(&table.widths, out)?;
+        }
+        Ok(())
+    }
+}
+
+/// Left-aligns `text` in a field `width` terminal columns wide.
+fn pad(text: &str, width: usize) -> String {
+    let fill = width.saturating_sub(display_width(text));
+    format!("{}{:fill$}", text, "", fill = fill)
+}
+
+/// Shared handle to the [`TableRenderer`] stored in a [`CSVFile`](crate::CSVFile).
+#[derive(Clone)]
+pub(crate) struct Renderer(Arc<dyn TableRenderer + Send + Sync>);
+
+impl Renderer {
+    pub(crate) fn new(renderer: impl TableRenderer + Send + Sync + 'static) -> Self {
+        Renderer(Arc::new(renderer))
+    }
+
+    pub(crate) fn render(&self, table: &Table, out: &mut dyn Write) -> io::Result<()> {
+        self.0.render(table, out)
+    }
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Renderer::new(BoxedTableRenderer::default())
+    }
+}
+
+impl Debug for Renderer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Renderer")
+    }
+}
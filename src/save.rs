@@ -0,0 +1,143 @@
+use std::{
+    fmt::{Display, Formatter},
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Whether writing over an existing file keeps a copy of its previous version.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Backup {
+    /// Replace the file without keeping a copy. This is the default.
+    #[default]
+    None,
+    /// Keep the previous version next to the file as `data.csv.bak`, replacing any older one.
+    Bak,
+    /// Keep every previous version next to the file as `data.csv.20240131-093000.bak`, stamped
+    /// with the time of the write in UTC.
+    Timestamped,
+}
+
+impl Backup {
+    /// Parses `none`, `bak` or `timestamp`, in any case.
+    pub fn parse(text: &str) -> Option<Self> {
+        match text.trim().to_ascii_lowercase().as_str() {
+            "none" => Some(Backup::None),
+            "bak" => Some(Backup::Bak),
+            "timestamp" | "timestamped" => Some(Backup::Timestamped),
+            _ => None,
+        }
+    }
+
+    /// Returns where the previous version of `path` is kept when it is written at `now`.
+    fn path_for(&self, path: &Path, now: SystemTime) -> Option<PathBuf> {
+        let suffix = match self {
+            Backup::None => return None,
+            Backup::Bak => "bak".to_owned(),
+            Backup::Timestamped => format!("{}.bak", timestamp(now)),
+        };
+        let mut name = path.file_name()?.to_os_string();
+        name.push(".");
+        name.push(suffix);
+        Some(path.with_file_name(name))
+    }
+}
+
+impl Display for Backup {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Backup::None => "none",
+            Backup::Bak => "bak",
+            Backup::Timestamped => "timestamp",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Writes `content` to `path` through a temporary file in the same directory that is renamed
+/// over it, so that a failed write leaves the previous version untouched. The previous version
+/// is copied first if `backup` asks for it, and its permissions carry over to the new one.
+///
+/// Returns the path of the backup, if one was made.
+pub(crate) fn write_atomically(
+    path: &Path,
+    content: &[u8],
+    backup: Backup,
+) -> io::Result<Option<PathBuf>> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "missing file name"))?;
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(file_name);
+    temp_name.push(format!(".csvr-{}.tmp", process::id()));
+    let temp_path = path.with_file_name(temp_name);
+
+    let result = write_and_replace(path, &temp_path, content, backup);
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+fn write_and_replace(
+    path: &Path,
+    temp_path: &Path,
+    content: &[u8],
+    backup: Backup,
+) -> io::Result<Option<PathBuf>> {
+    let mut temp = File::create(temp_path)?;
+    temp.write_all(content)?;
+    temp.sync_all()?;
+    drop(temp);
+
+    let previous = fs::metadata(path)
+        .ok()
+        .filter(|metadata| metadata.is_file());
+    let mut backup_path = None;
+    if let Some(metadata) = previous {
+        fs::set_permissions(temp_path, metadata.permissions())?;
+        backup_path = backup.path_for(path, SystemTime::now());
+        if let Some(backup_path) = &backup_path {
+            fs::copy(path, backup_path)?;
+        }
+    }
+    fs::rename(temp_path, path)?;
+    Ok(backup_path)
+}
+
+/// Formats `time` as `YYYYMMDD-HHMMSS` in UTC.
+fn timestamp(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    let (days, seconds) = (seconds / 86_400, seconds % 86_400);
+
+    // Converts days since 1970-01-01 into a civil date, counting in 400-year eras that start on
+    // the 1st of March so that leap days fall at the end of each year.
+    let days = days as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}",
+        year,
+        month,
+        day,
+        seconds / 3_600,
+        seconds % 3_600 / 60,
+        seconds % 60
+    )
+}
@@ -0,0 +1,105 @@
+use std::{
+    cmp::Ordering,
+    fmt::{Display, Formatter},
+};
+
+use crate::{
+    value::{parse_bool, parse_date, parse_integer, parse_number},
+    Cell,
+};
+
+/// The kind of values a column holds, inferred from its entries.
+///
+/// Empty entries and `_` placeholders are nulls and do not count towards the type. A column
+/// takes the first of integer, float, boolean and date that all of its other entries parse as,
+/// and is text otherwise, including when every entry is null.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColumnType {
+    /// Whole numbers, such as `42` or `-7`.
+    Integer,
+    /// Numbers with a fractional part or an exponent, such as `9.5` or `1e3`.
+    Float,
+    /// `true`/`false` or `yes`/`no`, in any case.
+    Boolean,
+    /// Dates in `YYYY-MM-DD` or `YYYY/MM/DD` form, optionally with a time of day.
+    Date,
+    /// Anything else.
+    Text,
+}
+
+impl ColumnType {
+    /// Infers the type of a column from its entries.
+    pub fn infer<'a>(entries: impl IntoIterator<Item = &'a Cell>) -> Self {
+        let values: Vec<&str> = entries
+            .into_iter()
+            .filter(|cell| !is_null(cell))
+            .map(Cell::as_str)
+            .collect();
+        if values.is_empty() {
+            return ColumnType::Text;
+        }
+        let all = |parses: fn(&str) -> bool| values.iter().all(|value| parses(value));
+        if all(|value| parse_integer(value).is_some()) {
+            ColumnType::Integer
+        } else if all(|value| parse_number(value).is_some()) {
+            ColumnType::Float
+        } else if all(|value| parse_bool(value).is_some()) {
+            ColumnType::Boolean
+        } else if all(|value| parse_date(value).is_some()) {
+            ColumnType::Date
+        } else {
+            ColumnType::Text
+        }
+    }
+
+    /// Returns whether the column holds integers or floats.
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, ColumnType::Integer | ColumnType::Float)
+    }
+
+    /// Compares two values as this type: numerically, `false` before `true` or chronologically.
+    /// Returns `None` for text or when either value does not parse as this type.
+    pub(crate) fn compare(&self, left: &str, right: &str) -> Option<Ordering> {
+        match self {
+            ColumnType::Integer | ColumnType::Float => parse_number(left)
+                .zip(parse_number(right))
+                .and_then(|(left, right)| left.partial_cmp(&right)),
+            ColumnType::Boolean => parse_bool(left)
+                .zip(parse_bool(right))
+                .map(|(left, right)| left.cmp(&right)),
+            ColumnType::Date => parse_date(left)
+                .zip(parse_date(right))
+                .map(|(left, right)| left.cmp(&right)),
+            ColumnType::Text => None,
+        }
+    }
+}
+
+impl Display for ColumnType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ColumnType::Integer => "integer",
+            ColumnType::Float => "float",
+            ColumnType::Boolean => "boolean",
+            ColumnType::Date => "date",
+            ColumnType::Text => "text",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The inferred type of one column, as returned by
+/// [`CSVFile::schema`](crate::CSVFile::schema).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnSchema {
+    /// The column title.
+    pub name: String,
+    pub column_type: ColumnType,
+    /// The number of empty or `_` entries in the column.
+    pub nulls: usize,
+}
+
+/// Returns whether an entry is missing: the `_` placeholder or blank text.
+pub(crate) fn is_null(cell: &Cell) -> bool {
+    cell.is_null() || cell.as_str().trim().is_empty()
+}
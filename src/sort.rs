@@ -0,0 +1,155 @@
+use std::cmp::Ordering;
+
+use crate::{
+    column::split_outside_quotes, schema::is_null, CSVError, Cell, ColumnSelector, ColumnType,
+};
+
+/// One column to sort rows by.
+///
+/// Keys are written as `column [asc|desc] [nocase] [natural]`, for example `price desc` or
+/// `name nocase natural`, and several keys are separated by commas.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SortKey {
+    pub column: ColumnSelector,
+    /// Whether larger values come first.
+    pub descending: bool,
+    /// Whether text is compared ignoring case.
+    pub case_insensitive: bool,
+    /// Whether runs of digits inside text are compared as numbers, so `file2` comes before
+    /// `file10`.
+    pub natural: bool,
+}
+
+impl SortKey {
+    /// Creates an ascending key on `column`.
+    pub fn new(column: impl Into<ColumnSelector>) -> Self {
+        SortKey {
+            column: column.into(),
+            descending: false,
+            case_insensitive: false,
+            natural: false,
+        }
+    }
+
+    /// Parses a comma-separated list of keys, such as `price desc, name asc nocase`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CSVError::InvalidSortKey`] if a key is empty or has an unknown modifier.
+    ///
+    pub fn parse_list(spec: &str) -> Result<Vec<SortKey>, CSVError> {
+        split_outside_quotes(spec, |ch| ch == ',')
+            .into_iter()
+            .map(SortKey::parse)
+            .collect()
+    }
+
+    fn parse(spec: &str) -> Result<SortKey, CSVError> {
+        let words = split_outside_quotes(spec, char::is_whitespace);
+        let (column, modifiers) = match words.split_first() {
+            Some((column, modifiers)) => (column, modifiers),
+            None => return Err(CSVError::InvalidSortKey(spec.trim().to_owned())),
+        };
+        let mut key = SortKey::new(ColumnSelector::parse(column));
+        for modifier in modifiers {
+            match modifier.to_ascii_lowercase().as_str() {
+                "asc" => key.descending = false,
+                "desc" => key.descending = true,
+                "nocase" => key.case_insensitive = true,
+                "natural" => key.natural = true,
+                _ => return Err(CSVError::InvalidSortKey(modifier.to_string())),
+            }
+        }
+        Ok(key)
+    }
+}
+
+/// A sort key bound to a column position and the type of its entries.
+pub(crate) struct ResolvedKey<'a> {
+    col: usize,
+    column_type: ColumnType,
+    key: &'a SortKey,
+}
+
+impl<'a> ResolvedKey<'a> {
+    pub(crate) fn new(key: &'a SortKey, col: usize, column_type: ColumnType) -> Self {
+        ResolvedKey {
+            col,
+            column_type,
+            key,
+        }
+    }
+
+    /// Compares two rows on this key. Empty and `_` entries come last in either direction.
+    pub(crate) fn compare(&self, left: &[Cell], right: &[Cell]) -> Ordering {
+        let (left, right) = (&left[self.col], &right[self.col]);
+        match (is_null(left), is_null(right)) {
+            (true, true) => return Ordering::Equal,
+            (true, false) => return Ordering::Greater,
+            (false, true) => return Ordering::Less,
+            (false, false) => {}
+        }
+        let (left, right) = (left.as_str(), right.as_str());
+        let ordering = self
+            .column_type
+            .compare(left, right)
+            .unwrap_or_else(|| compare_text(left, right, self.key));
+        if self.key.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
+
+fn compare_text(left: &str, right: &str, key: &SortKey) -> Ordering {
+    let (left, right) = if key.case_insensitive {
+        (left.to_lowercase(), right.to_lowercase())
+    } else {
+        (left.to_owned(), right.to_owned())
+    };
+    if key.natural {
+        compare_natural(&left, &right)
+    } else {
+        left.cmp(&right)
+    }
+}
+
+/// Compares text with runs of digits compared by their numeric value.
+fn compare_natural(left: &str, right: &str) -> Ordering {
+    let (mut left, mut right) = (left.chars().peekable(), right.chars().peekable());
+    loop {
+        match (left.peek().copied(), right.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(l), Some(r)) if l.is_ascii_digit() && r.is_ascii_digit() => {
+                let take_digits = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+                    let mut digits = String::new();
+                    while let Some(ch) = chars.peek().filter(|ch| ch.is_ascii_digit()) {
+                        digits.push(*ch);
+                        chars.next();
+                    }
+                    digits
+                };
+                let (l, r) = (take_digits(&mut left), take_digits(&mut right));
+                let (l_trimmed, r_trimmed) = (l.trim_start_matches('0'), r.trim_start_matches('0'));
+                let ordering = l_trimmed
+                    .len()
+                    .cmp(&r_trimmed.len())
+                    .then_with(|| l_trimmed.cmp(r_trimmed))
+                    .then_with(|| l.len().cmp(&r.len()));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(l), Some(r)) => {
+                if l != r {
+                    return l.cmp(&r);
+                }
+                left.next();
+                right.next();
+            }
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+
+use crate::{schema::is_null, value::parse_number, Cell, ColumnType};
+
+/// Number of most frequent values kept in [`ColumnStats::top`].
+pub const TOP_VALUES: usize = 5;
+
+/// A profile of one column, as returned by [`CSVFile::column_stats`](crate::CSVFile::column_stats).
+///
+/// Empty and `_` entries are nulls and are left out of every statistic but `nulls`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnStats {
+    /// The column title.
+    pub name: String,
+    pub column_type: ColumnType,
+    /// The number of entries that are not null.
+    pub count: usize,
+    /// The number of empty or `_` entries.
+    pub nulls: usize,
+    /// The number of different entries that are not null.
+    pub distinct: usize,
+    /// The smallest entry, compared according to the column type.
+    pub min: Option<String>,
+    /// The largest entry, compared according to the column type.
+    pub max: Option<String>,
+    /// The mean of a numeric column.
+    pub mean: Option<f64>,
+    /// The median of a numeric column.
+    pub median: Option<f64>,
+    /// The sample standard deviation of a numeric column with at least two entries.
+    pub std_dev: Option<f64>,
+    /// The [`TOP_VALUES`] most frequent entries with their number of occurrences, most frequent
+    /// first and ties in order of first appearance.
+    pub top: Vec<(String, usize)>,
+}
+
+impl ColumnStats {
+    pub(crate) fn compute(name: &str, column_type: ColumnType, entries: &[&Cell]) -> Self {
+        let values: Vec<&str> = entries
+            .iter()
+            .filter(|cell| !is_null(cell))
+            .map(|cell| cell.as_str())
+            .collect();
+
+        let mut frequencies: Vec<(&str, usize)> = Vec::new();
+        let mut positions: HashMap<&str, usize> = HashMap::new();
+        for value in values.iter() {
+            match positions.get(value) {
+                Some(&position) => frequencies[position].1 += 1,
+                None => {
+                    positions.insert(value, frequencies.len());
+                    frequencies.push((value, 1));
+                }
+            }
+        }
+        let distinct = frequencies.len();
+        frequencies.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        frequencies.truncate(TOP_VALUES);
+
+        let compare = |left: &&&str, right: &&&str| {
+            column_type
+                .compare(left, right)
+                .unwrap_or_else(|| left.cmp(right))
+        };
+        let min = values.iter().min_by(compare).map(|value| value.to_string());
+        let max = values.iter().max_by(compare).map(|value| value.to_string());
+
+        let (mean, median, std_dev) = if column_type.is_numeric() && !values.is_empty() {
+            let mut numbers: Vec<f64> = values
+                .iter()
+                .filter_map(|value| parse_number(value))
+                .collect();
+            numbers.sort_by(|left, right| left.total_cmp(right));
+            let n = numbers.len() as f64;
+            let mean = numbers.iter().sum::<f64>() / n;
+            let middle = numbers.len() / 2;
+            let median = if numbers.len().is_multiple_of(2) {
+                (numbers[middle - 1] + numbers[middle]) / 2.0
+            } else {
+                numbers[middle]
+            };
+            let std_dev = (numbers.len() > 1).then(|| {
+                let squares: f64 = numbers.iter().map(|number| (number - mean).powi(2)).sum();
+                (squares / (n - 1.0)).sqrt()
+            });
+            (Some(mean), Some(median), std_dev)
+        } else {
+            (None, None, None)
+        };
+
+        ColumnStats {
+            name: name.to_owned(),
+            column_type,
+            count: values.len(),
+            nulls: entries.len() - values.len(),
+            distinct,
+            min,
+            max,
+            mean,
+            median,
+            std_dev,
+            top: frequencies
+                .into_iter()
+                .map(|(value, count)| (value.to_owned(), count))
+                .collect(),
+        }
+    }
+
+    /// Returns the title and formatted value of each statistic, in display order.
+    pub(crate) fn fields(&self) -> Vec<(&'static str, String)> {
+        let optional = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_owned());
+        let number = |value: Option<f64>| value.map_or_else(|| "-".to_owned(), format_number);
+        let top = self
+            .top
+            .iter()
+            .map(|(value, count)| format!("{} ({})", value, count))
+            .collect::<Vec<_>>()
+            .join(", ");
+        vec![
+            ("Column", self.name.clone()),
+            ("Type", self.column_type.to_string()),
+            ("Count", self.count.to_string()),
+            ("Nulls", self.nulls.to_string()),
+            ("Distinct", self.distinct.to_string()),
+            ("Min", optional(&self.min)),
+            ("Max", optional(&self.max)),
+            ("Mean", number(self.mean)),
+            ("Median", number(self.median)),
+            ("Std Dev", number(self.std_dev)),
+            ("Top", top),
+        ]
+    }
+}
+
+/// Formats a statistic with at most four decimal places, dropping trailing zeros.
+fn format_number(number: f64) -> String {
+    let text = format!("{:.4}", number);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    match text {
+        "-0" => "0".to_owned(),
+        text => text.to_owned(),
+    }
+}
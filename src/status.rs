@@ -0,0 +1,32 @@
+use std::io::{self, Write};
+
+/// Where a [`CSVFile`](crate::CSVFile) prints the status messages of its mutations, such as
+/// `Successfully Deleted Row`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StatusOutput {
+    /// Print to standard output. This is the default.
+    #[default]
+    Stdout,
+    /// Print to standard error, keeping standard output free for data.
+    Stderr,
+    /// Do not print status messages.
+    Silent,
+}
+
+impl StatusOutput {
+    /// Returns a writer for status messages.
+    pub fn writer(&self) -> Box<dyn Write> {
+        match self {
+            StatusOutput::Stdout => Box::new(io::stdout()),
+            StatusOutput::Stderr => Box::new(io::stderr()),
+            StatusOutput::Silent => Box::new(io::sink()),
+        }
+    }
+}
+
+/// Prints a line to the status output of a [`CSVFile`](crate::CSVFile).
+macro_rules! status {
+    ($file:expr, $($arg:tt)*) => {{
+        let _ = writeln!($file.status_output.writer(), $($arg)*);
+    }};
+}
@@ -1,6 +1,50 @@
 #[cfg(test)]
 mod tests {
-    use csvr::{CSVError, FileDataUtils};
+    use crate::cli::{expand_paths, parse_args, FileLoadError};
+    use crate::query_resolver::query_resolver;
+    use csvr::{
+        Aggregate, AlwaysNo, AlwaysYes, Backup, BoxedTableRenderer, CSVError, CallbackConfirm,
+        Cell, ColumnSchema, ColumnSelector, ColumnType, Dialect, FileDataUtils, Framing, JoinKey,
+        JoinKind, LineTerminator, MergeOptions, SortKey, StatusOutput, Table, TableRenderer,
+        TextEncoding,
+    };
+    use std::{
+        env, fs, io,
+        path::PathBuf,
+        sync::{Arc, Mutex},
+    };
+
+    fn table(num_rows: usize, num_cols: usize) -> csvr::CSVFile {
+        let header = (1..=num_cols).map(|col| format!("col{}", col)).collect();
+        let rows = (1..=num_rows)
+            .map(|row| {
+                (1..=num_cols)
+                    .map(|col| Cell::from(format!("{}{}", row, col)))
+                    .collect()
+            })
+            .collect();
+        csvr::CSVFile::from_rows(header, rows).unwrap()
+    }
+
+    fn write_temp(name: &str, content: impl AsRef<[u8]>) -> PathBuf {
+        let path = env::temp_dir().join(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn load_temp(name: &str, content: &str) -> csvr::CSVFile {
+        let path = write_temp(name, content);
+        let file = csvr::CSVFile::open(&path.to_string_lossy()).unwrap();
+        let _ = fs::remove_file(path);
+        file
+    }
+
+    fn cells(file: &csvr::CSVFile) -> Vec<Vec<String>> {
+        file.rows()
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+            .collect()
+    }
 
     #[test]
     fn test_display_file_incorrect_row_index() {
@@ -17,10 +61,7 @@ mod tests {
 
     #[test]
     fn test_display_file_incorrect_indices() {
-        let mut default_file: csvr::CSVFile = Default::default();
-        default_file.file_data.push("1,2,3,4".to_owned());
-        default_file.file_data.push("1,2,3,4".to_owned());
-        default_file.num_rows = 2;
+        let default_file = table(2, 4);
         let result = default_file.display_file(Some(2), Some(1));
         if let Err(error) = result {
             let err = error.downcast_ref::<CSVError>().unwrap();
@@ -30,8 +71,7 @@ mod tests {
 
     #[test]
     fn test_delete_row_invalid_row_index() {
-        let mut default_file: csvr::CSVFile = Default::default();
-        default_file.num_rows = 2;
+        let mut default_file = table(2, 0);
         let result = default_file.delete_row(20);
         if let Err(error) = result {
             let err = error.downcast_ref::<CSVError>().unwrap();
@@ -41,8 +81,7 @@ mod tests {
 
     #[test]
     fn test_delete_col_invalid_col_index() {
-        let mut default_file: csvr::CSVFile = Default::default();
-        default_file.num_cols = 2;
+        let mut default_file = table(0, 2);
         let result = default_file.delete_column(20);
         if let Err(error) = result {
             let err = error.downcast_ref::<CSVError>().unwrap();
@@ -52,8 +91,7 @@ mod tests {
 
     #[test]
     fn test_delete_entry_invalid_row_index() {
-        let mut default_file: csvr::CSVFile = Default::default();
-        default_file.num_rows = 2;
+        let mut default_file = table(2, 0);
         let result = default_file.delete_entry(20, 0);
         if let Err(error) = result {
             let err = error.downcast_ref::<CSVError>().unwrap();
@@ -63,9 +101,7 @@ mod tests {
 
     #[test]
     fn test_delete_entry_invalid_col_index() {
-        let mut default_file: csvr::CSVFile = Default::default();
-        default_file.num_cols = 2;
-        default_file.num_rows = 1;
+        let mut default_file = table(1, 2);
         let result = default_file.delete_entry(1, 20);
         if let Err(error) = result {
             let err = error.downcast_ref::<CSVError>().unwrap();
@@ -75,10 +111,8 @@ mod tests {
 
     #[test]
     fn test_delete_entry_invalid_entry() {
-        let mut default_file: csvr::CSVFile = Default::default();
-        default_file.num_cols = 1;
-        default_file.num_rows = 1;
-        default_file.file_data.push("_".to_owned());
+        let mut default_file = table(1, 1);
+        default_file.set_cell(1, 1, Cell::Null).unwrap();
         let result = default_file.delete_entry(1, 1);
         if let Err(error) = result {
             let err = error.downcast_ref::<CSVError>().unwrap();
@@ -98,8 +132,7 @@ mod tests {
 
     #[test]
     fn test_modify_row_more_entries() {
-        let mut default_file: csvr::CSVFile = Default::default();
-        default_file.num_rows = 1;
+        let mut default_file = table(1, 0);
         let result = default_file.modify_row(1, "new".to_owned());
         if let Err(error) = result {
             let err = error.downcast_ref::<CSVError>().unwrap();
@@ -109,9 +142,7 @@ mod tests {
 
     #[test]
     fn test_modify_row_less_entries() {
-        let mut default_file: csvr::CSVFile = Default::default();
-        default_file.num_rows = 1;
-        default_file.num_cols = 2;
+        let mut default_file = table(1, 2);
         let result = default_file.modify_row(1, "new".to_owned());
         if let Err(error) = result {
             let err = error.downcast_ref::<CSVError>().unwrap();
@@ -131,8 +162,7 @@ mod tests {
 
     #[test]
     fn test_modify_col_more_entries() {
-        let mut default_file: csvr::CSVFile = Default::default();
-        default_file.num_cols = 1;
+        let mut default_file = table(0, 1);
         let result = default_file.modify_column(1, "a".to_owned());
         if let Err(error) = result {
             let err = error.downcast_ref::<CSVError>().unwrap();
@@ -142,9 +172,7 @@ mod tests {
 
     #[test]
     fn test_modify_col_less_entries() {
-        let mut default_file: csvr::CSVFile = Default::default();
-        default_file.num_rows = 2;
-        default_file.num_cols = 1;
+        let mut default_file = table(2, 1);
         let result = default_file.modify_column(1, "new".to_owned());
         if let Err(error) = result {
             let err = error.downcast_ref::<CSVError>().unwrap();
@@ -164,8 +192,7 @@ mod tests {
 
     #[test]
     fn test_modify_entry_invalid_col_index() {
-        let mut default_file: csvr::CSVFile = Default::default();
-        default_file.num_rows = 1;
+        let mut default_file = table(1, 0);
         let result = default_file.update_entry(1, 1, "new".to_owned());
         if let Err(error) = result {
             let err = error.downcast_ref::<CSVError>().unwrap();
@@ -175,8 +202,7 @@ mod tests {
 
     #[test]
     fn test_merge_files_invalid_dimensions() {
-        let mut default_file: csvr::CSVFile = Default::default();
-        default_file.num_cols = 1;
+        let mut default_file = table(0, 1);
         let other_file: csvr::CSVFile = Default::default();
         let result = default_file.merge_files(&other_file);
         if let Err(error) = result {
@@ -187,9 +213,7 @@ mod tests {
 
     #[test]
     fn test_add_row_less_entries() {
-        let mut default_file: csvr::CSVFile = Default::default();
-        default_file.num_cols = 2;
-
+        let mut default_file = table(0, 2);
         let result = default_file.add_row("a".to_owned());
         if let Err(error) = result {
             let err = error.downcast_ref::<CSVError>().unwrap();
@@ -209,9 +233,7 @@ mod tests {
 
     #[test]
     fn test_add_col_less_entries() {
-        let mut default_file: csvr::CSVFile = Default::default();
-        default_file.num_rows = 1;
-
+        let mut default_file = table(1, 0);
         let result = default_file.add_column("".to_owned());
         if let Err(error) = result {
             let err = error.downcast_ref::<CSVError>().unwrap();
@@ -228,6 +250,1167 @@ mod tests {
             assert_eq!(err, &CSVError::MoreEntriesThanRequired);
         }
     }
+
+    #[test]
+    fn test_parse_record_quoted_fields() {
+        let fields = Dialect::default().parse_record(r#"1,"Smith, John","He said ""hi""",,end"#);
+        assert_eq!(
+            fields,
+            vec!["1", "Smith, John", r#"He said "hi""#, "", "end"]
+        );
+    }
+
+    #[test]
+    fn test_format_record_requotes_fields() {
+        let fields = vec!["Smith, John", r#"say "hi""#, "multi\nline", "plain"];
+        let record = Dialect::default().format_record(&fields);
+        assert_eq!(
+            record,
+            "\"Smith, John\",\"say \"\"hi\"\"\",\"multi\nline\",plain"
+        );
+        assert_eq!(Dialect::default().parse_record(&record), fields);
+    }
+
+    #[test]
+    fn test_split_records_quoted_line_breaks() {
+        let records = Dialect::default().split_records("a,b\r\n\"x\ny\",z\r\nc,d\n");
+        assert_eq!(records, vec!["a,b", "\"x\ny\",z", "c,d"]);
+    }
+
+    #[test]
+    fn test_split_records_quote_inside_field() {
+        let content = "id,size\n1,5\" screen\n2,7\" tab\n3,ok\n";
+        let records = Dialect::default().split_records(content);
+        assert_eq!(
+            records,
+            vec!["id,size", "1,5\" screen", "2,7\" tab", "3,ok"]
+        );
+
+        let file = load_temp("inch_mark.csv", content);
+        assert_eq!(file.num_rows(), 3);
+        assert_eq!(file.rows()[0][1].as_str(), "5\" screen");
+    }
+
+    #[test]
+    fn test_load_and_write_round_trip() {
+        let content = "id,name,quote\n1,\"Smith, John\",\"\"\"ok\"\"\"\n2,Doe,plain";
+        let source = write_temp("csvr_round_trip_source.csv", content);
+        let target = env::temp_dir().join("csvr_round_trip_target.csv");
+
+        let mut file = csvr::CSVFile::open(&source.to_string_lossy()).unwrap();
+        assert_eq!(file.num_rows(), 2);
+        assert_eq!(file.num_cols(), 3);
+        assert_eq!(file.cell(1, 2), Some(&Cell::from("Smith, John")));
+
+        file.file_path = target.to_string_lossy().into_owned();
+        file.write_to_file().unwrap();
+        assert_eq!(fs::read_to_string(&target).unwrap(), content);
+
+        let _ = fs::remove_file(source);
+        let _ = fs::remove_file(target);
+    }
+
+    #[test]
+    fn test_dialect_from_extension() {
+        assert_eq!(Dialect::from_path("data.tsv").unwrap().delimiter, '\t');
+        assert_eq!(Dialect::from_path("data.PSV").unwrap().delimiter, '|');
+        assert_eq!(Dialect::from_path("data.csv"), Some(Dialect::default()));
+        assert_eq!(Dialect::from_path("data.txt"), None);
+    }
+
+    #[test]
+    fn test_dialect_semicolon_and_escape() {
+        let dialect = Dialect {
+            delimiter: ';',
+            quote: '\'',
+            escape: Some('\\'),
+            ..Default::default()
+        };
+        let fields = dialect.parse_record(r"1;'Smith; John';'it\'s';a,b");
+        assert_eq!(fields, vec!["1", "Smith; John", "it's", "a,b"]);
+        let record = dialect.format_record(&fields);
+        assert_eq!(record, r"1;'Smith; John';'it\'s';a,b");
+    }
+
+    #[test]
+    fn test_load_tsv_and_add_column() {
+        let path = write_temp("csvr_dialect_test.tsv", "a\tb\n1\t\"x\ty\"\n");
+        let file = csvr::CSVFile::open(&path.to_string_lossy()).unwrap();
+        assert_eq!(file.dialect.delimiter, '\t');
+        assert_eq!(file.num_cols(), 2);
+        assert_eq!(file.cell(1, 2), Some(&Cell::from("x\ty")));
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_parse_args_dialect_options() {
+        let args = ["csvr", "data.txt", "--delimiter", "semicolon", "-Q", "'"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        let options = parse_args(args).unwrap();
+        assert_eq!(options.file_name, "data.txt");
+        let dialect = options.dialect().unwrap();
+        assert_eq!(dialect.delimiter, ';');
+        assert_eq!(dialect.quote, '\'');
+
+        let args = vec!["csvr".to_owned(), "data.csv".to_owned(), "-D".to_owned()];
+        assert_eq!(
+            parse_args(args),
+            Err(FileLoadError::MissingOptionValue("-D".to_owned()))
+        );
+    }
+
+    #[test]
+    fn test_parse_args_batch_commands() {
+        let args = [
+            "csvr",
+            "data.csv",
+            "-c",
+            "delete row 3",
+            "--command",
+            "write",
+        ]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+        let options = parse_args(args).unwrap();
+        assert_eq!(options.commands, vec!["delete row 3", "write"]);
+        assert!(options.is_batch());
+
+        let args = vec!["csvr".to_owned(), "data.csv".to_owned()];
+        assert!(!parse_args(args).unwrap().is_batch());
+    }
+
+    #[test]
+    fn test_query_resolver_reports_failures() {
+        let mut file = load_temp("csvr_query_resolver.csv", "a,b\n1,2\n3,4\n");
+        assert_eq!(query_resolver("delete row 1".to_owned(), &mut file), Ok(()));
+        assert_eq!(file.num_rows(), 1);
+        assert_eq!(
+            query_resolver("delete row 5".to_owned(), &mut file),
+            Err("Row with entered index doesn't exist".to_owned())
+        );
+        assert!(query_resolver("frobnicate".to_owned(), &mut file).is_err());
+    }
+
+    #[test]
+    fn test_read_from_reader_and_write_to_writer() {
+        let input = "id;name\n1;\"a;b\"\n2;c\n";
+        let options = csvr::LoadOptions::default();
+        let mut file = csvr::CSVFile::from_reader(input.as_bytes(), &options).unwrap();
+        assert_eq!(file.dialect.delimiter, ';');
+        assert_eq!(file.file_path, "");
+
+        file.delete_row(2).unwrap();
+        let mut out = Vec::new();
+        file.write_to(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "id;name\n1;\"a;b\"\n");
+
+        let single = csvr::CSVFile::from_reader("name\nasha\n".as_bytes(), &options).unwrap();
+        assert_eq!(single.dialect, Dialect::default());
+    }
+
+    #[test]
+    fn test_parse_args_stdin_and_output() {
+        let args = ["csvr", "-", "-c", "write", "-o", "-", "-q"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        let options = parse_args(args).unwrap();
+        assert_eq!(options.file_name, "-");
+        assert_eq!(options.output, Some("-".to_owned()));
+        assert_eq!(options.status_output(), StatusOutput::Silent);
+
+        let args = vec!["csvr".to_owned(), "-".to_owned()];
+        assert_eq!(parse_args(args), Err(FileLoadError::StdinWithoutCommands));
+    }
+
+    #[test]
+    fn test_sniff_delimiter_and_line_terminator() {
+        let sample = "id;name;price\r\n1;\"Smith; John\";2,50\r\n2;Doe;3,00\r\n";
+        let dialect = Dialect::sniff(sample).unwrap();
+        assert_eq!(dialect.delimiter, ';');
+        assert_eq!(dialect.quote, '"');
+        assert_eq!(dialect.line_terminator, LineTerminator::CrLf);
+
+        let dialect = Dialect::sniff("a\tb\tc\n1\t2\t3\n").unwrap();
+        assert_eq!(dialect.delimiter, '\t');
+        assert_eq!(Dialect::sniff("single\ncolumn\n"), None);
+    }
+
+    #[test]
+    fn test_sniff_header_presence() {
+        let with_header = Dialect::detect("name,age\nasha,31\nravi,27\n").unwrap();
+        assert!(with_header.has_header);
+        let without_header = Dialect::detect("asha,31\nravi,27\nmeena,45\n").unwrap();
+        assert!(!without_header.has_header);
+    }
+
+    #[test]
+    fn test_open_load_errors() {
+        let missing = env::temp_dir().join("csvr_missing_file.csv");
+        let _ = fs::remove_file(&missing);
+        assert_eq!(
+            csvr::CSVFile::open(&missing.to_string_lossy()),
+            Err(CSVError::FileNotFound)
+        );
+
+        let empty = write_temp("csvr_empty_file.csv", "");
+        assert_eq!(
+            csvr::CSVFile::open(&empty.to_string_lossy()),
+            Err(CSVError::EmptyFile)
+        );
+
+        let latin1 = write_temp("csvr_latin1_file.csv", b"name\ncaf\xe9\n");
+        let utf8 = csvr::LoadOptions {
+            encoding: Some(TextEncoding::Utf8),
+            ..Default::default()
+        };
+        assert_eq!(
+            csvr::CSVFile::open_with_options(&latin1.to_string_lossy(), &utf8),
+            Err(CSVError::InvalidEncoding)
+        );
+
+        let unknown = write_temp("csvr_unknown_dialect.dat", "single\ncolumn\n");
+        assert_eq!(
+            csvr::CSVFile::open(&unknown.to_string_lossy()),
+            Err(CSVError::UnknownDialect)
+        );
+
+        for path in [empty, latin1, unknown] {
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    #[test]
+    fn test_mutations_confirmed_by_default() {
+        let mut file = load_temp("csvr_confirm_default.csv", "a,b\n1,2\n3,4\n");
+        file.delete_row(1).unwrap();
+        assert_eq!(file.num_rows(), 1);
+        assert_eq!(file.rows(), [vec![Cell::from("3"), Cell::from("4")]]);
+    }
+
+    #[test]
+    fn test_mutations_rejected_by_confirm() {
+        let mut file = load_temp("csvr_confirm_no.csv", "a,b\n1,2\n3,4\n");
+        file.set_confirm(AlwaysNo);
+        file.delete_column(1).unwrap();
+        file.add_row("5,6".to_owned()).unwrap();
+        assert_eq!(file.num_cols(), 2);
+        assert_eq!(file.num_rows(), 2);
+        assert_eq!(file.cell(2, 1), Some(&Cell::from("3")));
+    }
+
+    #[test]
+    fn test_callback_confirm_receives_prompt() {
+        let prompts = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&prompts);
+        let mut file = load_temp("csvr_confirm_callback.csv", "a,b\n1,2\n");
+        file.set_confirm(CallbackConfirm(move |prompt: &str| {
+            seen.lock().unwrap().push(prompt.to_owned());
+            false
+        }));
+        file.update_entry(1, 2, "9".to_owned()).unwrap();
+        assert_eq!(file.cell(1, 2), Some(&Cell::from("2")));
+        assert_eq!(
+            *prompts.lock().unwrap(),
+            vec!["Are you sure you want to update the entry: 2"]
+        );
+    }
+
+    #[test]
+    fn test_render_file_into_writer() {
+        let mut file = load_temp("csvr_render_file.csv", "name,age\nasha,31\nravi,27\n");
+        file.set_renderer(BoxedTableRenderer { color: false });
+        let mut out = Vec::new();
+        file.render_file(&mut out, Some(2), None).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "+------+-----+\n\
+             | name | age |\n\
+             +------+-----+\n\
+             | ravi | 27  |  (2)\n\
+             +------+-----+\n"
+        );
+
+        let mut out = Vec::new();
+        file.render_column(&mut out, 2).unwrap();
+        assert!(String::from_utf8(out).unwrap().contains("| 31  |  (1)"));
+    }
+
+    #[test]
+    fn test_custom_table_renderer() {
+        struct Markdown;
+        impl TableRenderer for Markdown {
+            fn render(&self, table: &Table, out: &mut dyn io::Write) -> io::Result<()> {
+                writeln!(out, "| {} |", table.header.join(" | "))?;
+                for (_, items) in &table.rows {
+                    writeln!(out, "| {} |", items.join(" | "))?;
+                }
+                Ok(())
+            }
+        }
+
+        let mut file = load_temp("csvr_render_custom.csv", "name,age\nasha,31\n");
+        file.set_renderer(Markdown);
+        let mut out = Vec::new();
+        file.render_file(&mut out, None, None).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "| name | age |\n| asha | 31 |\n"
+        );
+    }
+
+    #[test]
+    fn test_cell_access_and_counters() {
+        let mut file = load_temp("csvr_cells.csv", "a,b\n1,_\n3,4\n");
+        assert_eq!(file.header(), ["a", "b"]);
+        assert_eq!(file.cell(1, 2), Some(&Cell::Null));
+        assert_eq!(file.cell(3, 1), None);
+        assert_eq!(file.cell(0, 1), None);
+
+        assert_eq!(file.set_cell(2, 1, "x"), Ok(Cell::from("3")));
+        assert_eq!(file.row(2), Some(&[Cell::from("x"), Cell::from("4")][..]));
+        assert_eq!(file.set_cell(1, 3, "y"), Err(CSVError::InvalidColIndex));
+
+        file.add_column("c,5,6".to_owned()).unwrap();
+        file.delete_row(1).unwrap();
+        assert_eq!(file.header(), ["a", "b", "c"]);
+        assert_eq!((file.num_rows(), file.num_cols()), (1, 3));
+        assert_eq!(file.max_col_lengths(), vec![1, 1, 1]);
+    }
+
+    #[test]
+    fn test_column_widths_follow_edits() {
+        let mut file = load_temp("csvr_widths.csv", "id,name\n1,ravi\n");
+        assert_eq!(file.max_col_lengths(), vec![2, 4]);
+
+        file.add_column("city,東京".to_owned()).unwrap();
+        file.update_entry(1, 2, "Ravindranath".to_owned()).unwrap();
+        assert_eq!(file.max_col_lengths(), vec![2, 12, 4]);
+
+        file.delete_column(2).unwrap();
+        file.modify_row(1, "100,😀".to_owned()).unwrap();
+        assert_eq!(file.max_col_lengths(), vec![3, 4]);
+
+        file.delete_row(1).unwrap();
+        assert_eq!(file.max_col_lengths(), vec![2, 4]);
+
+        file.set_renderer(BoxedTableRenderer { color: false });
+        file.add_row("7,東京".to_owned()).unwrap();
+        let mut out = Vec::new();
+        file.render_file(&mut out, None, None).unwrap();
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .contains("| 7  | 東京 |  (1)"));
+    }
+
+    #[test]
+    fn test_load_ragged_rows() {
+        let load = |content: &str| {
+            csvr::CSVFile::from_reader(content.as_bytes(), &csvr::LoadOptions::default())
+        };
+        assert_eq!(
+            load("x,y\n1,2,3\n\n4,5\n"),
+            Err(CSVError::RaggedRow(1, 3, 2))
+        );
+        assert_eq!(load("x,y\n1,2\n\n4,5\n"), Err(CSVError::RaggedRow(2, 1, 2)));
+
+        let file = load("x,y\n1,2\n4,5\n\n\n").unwrap();
+        assert_eq!(file.num_rows(), 2);
+
+        let content = "name\nasha\n\nravi\n";
+        let file = load(content).unwrap();
+        assert_eq!(file.num_rows(), 3);
+        assert_eq!(file.cell(2, 1), Some(&Cell::from("")));
+        let mut out = Vec::new();
+        file.write_to(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), content);
+    }
+
+    #[test]
+    fn test_load_single_column_files() {
+        let times = load_temp("csvr_single_times.csv", "time\n10:30\n11:45\n12:00\n");
+        assert_eq!(times.header(), ["time"]);
+        assert_eq!(times.cell(2, 1), Some(&Cell::from("11:45")));
+        assert_eq!(times.dialect.delimiter, ',');
+
+        let urls = load_temp("csvr_single_urls.csv", "url\nhttp://a.com\nhttp://b.com\n");
+        assert_eq!(urls.header(), ["url"]);
+        assert_eq!(urls.cell(1, 1), Some(&Cell::from("http://a.com")));
+        assert_eq!(Dialect::sniff("url\nhttp://a.com\nhttp://b.com\n"), None);
+    }
+
+    #[test]
+    fn test_filter_expressions() {
+        let file = load_temp(
+            "csvr_filter.csv",
+            "name,age,city\nasha,31,Pune\nravi,27,Goa\nmeena,45,Pune\nzed,_,\n",
+        );
+        let rows = |filter: &str| file.matching_rows(filter).unwrap();
+        assert_eq!(rows("age > 30 and city = \"Pune\""), vec![1, 3]);
+        assert_eq!(rows("age >= 9"), vec![1, 2, 3]);
+        assert_eq!(rows("city in (Goa, 'Delhi') or age is empty"), vec![2, 4]);
+        assert_eq!(rows("name like '%a' and not (city is empty)"), vec![1, 3]);
+        assert_eq!(rows("NOT name LIKE '_a%'"), vec![1, 3, 4]);
+
+        assert_eq!(
+            file.matching_rows("salary > 10"),
+            Err(CSVError::UnknownColumn("salary".to_owned()))
+        );
+        assert!(matches!(
+            file.matching_rows("age > "),
+            Err(CSVError::InvalidFilter(_))
+        ));
+        assert!(matches!(
+            file.matching_rows("(age > 3"),
+            Err(CSVError::InvalidFilter(_))
+        ));
+    }
+
+    #[test]
+    fn test_delete_and_modify_where() {
+        let mut file = load_temp(
+            "csvr_filter_mutations.csv",
+            "name,age,city\nasha,31,Pune\nravi,27,Goa\nmeena,45,Pune\n",
+        );
+        query_resolver(
+            "modify where city = 'Pune' set city = \"New  Delhi\"".to_owned(),
+            &mut file,
+        )
+        .unwrap();
+        assert_eq!(file.cell(3, 3), Some(&Cell::from("New  Delhi")));
+        assert_eq!(file.cell(2, 3), Some(&Cell::from("Goa")));
+
+        file.delete_where("age < 40").unwrap();
+        assert_eq!(file.num_rows(), 1);
+        assert_eq!(file.cell(1, 1), Some(&Cell::from("meena")));
+    }
+
+    #[test]
+    fn test_column_selectors() {
+        assert_eq!(ColumnSelector::parse("3"), ColumnSelector::Index(3));
+        assert_eq!(ColumnSelector::parse("city"), "city".into());
+        assert_eq!(ColumnSelector::parse("'2019'"), "2019".into());
+        assert_eq!(ColumnSelector::parse("\"first name\""), "first name".into());
+
+        let mut file = load_temp(
+            "csvr_selectors.csv",
+            "id,first name,2019\n1,asha,x\n2,ravi,y\n",
+        );
+        assert_eq!(file.column_index("first name"), Ok(2));
+        assert_eq!(file.column_index(3), Ok(3));
+        assert_eq!(
+            file.column_index("last name"),
+            Err(CSVError::UnknownColumn("last name".to_owned()))
+        );
+        assert_eq!(file.cell(2, "first name"), Some(&Cell::from("ravi")));
+
+        query_resolver("modify item 1 \"first name\" Asha".to_owned(), &mut file).unwrap();
+        query_resolver("delete col '2019'".to_owned(), &mut file).unwrap();
+        query_resolver("add row 3,\"O'Brien, P\"".to_owned(), &mut file).unwrap();
+        assert_eq!(file.header(), ["id", "first name"]);
+        assert_eq!(file.cell(1, 2), Some(&Cell::from("Asha")));
+        assert_eq!(file.cell(3, "first name"), Some(&Cell::from("O'Brien, P")));
+        assert!(query_resolver("display col 2019".to_owned(), &mut file).is_err());
+    }
+
+    #[test]
+    fn test_sorted_row_order() {
+        let file = load_temp(
+            "csvr_sort_order.csv",
+            "name,price,date\nfile10,9.5,2024-02-01\nFile2,10,2023-12-31\nfile1,_,2024/01/15 09:30\nfile2,10,\n",
+        );
+        let order = |keys: &str| file.sorted_row_order(&SortKey::parse_list(keys).unwrap());
+        assert_eq!(order("price"), Ok(vec![1, 2, 4, 3]));
+        assert_eq!(order("price desc, name"), Ok(vec![2, 4, 1, 3]));
+        assert_eq!(order("date"), Ok(vec![2, 3, 1, 4]));
+        assert_eq!(order("name"), Ok(vec![2, 3, 1, 4]));
+        assert_eq!(order("name nocase natural"), Ok(vec![3, 2, 4, 1]));
+        assert_eq!(order("3 desc"), Ok(vec![1, 3, 2, 4]));
+        assert_eq!(
+            SortKey::parse_list("price upward"),
+            Err(CSVError::InvalidSortKey("upward".to_owned()))
+        );
+        assert_eq!(
+            order("cost"),
+            Err(CSVError::UnknownColumn("cost".to_owned()))
+        );
+    }
+
+    #[test]
+    fn test_sort_by_apply() {
+        let mut file = load_temp("csvr_sort_apply.csv", "item,qty\npen,12\nbook,3\nbag,40\n");
+        query_resolver("sort by qty desc".to_owned(), &mut file).unwrap();
+        assert_eq!(file.cell(1, 1), Some(&Cell::from("pen")));
+
+        query_resolver("sort by qty desc apply".to_owned(), &mut file).unwrap();
+        let items: Vec<&str> = file.rows().iter().map(|row| row[0].as_str()).collect();
+        assert_eq!(items, ["bag", "pen", "book"]);
+        assert!(query_resolver("sort qty".to_owned(), &mut file).is_err());
+        assert!(query_resolver("sort by apply".to_owned(), &mut file).is_err());
+    }
+
+    #[test]
+    fn test_column_type_inference() {
+        let file = load_temp(
+            "csvr_schema.csv",
+            "id,price,active,joined,name,notes\n1,9.5,yes,2024-01-31,asha,_\n2,10,No,2023/12/01 10:00,ravi,\n_,1e3,TRUE,,42,_\n",
+        );
+        let types: Vec<ColumnType> = file
+            .schema()
+            .into_iter()
+            .map(|column| column.column_type)
+            .collect();
+        assert_eq!(
+            types,
+            [
+                ColumnType::Integer,
+                ColumnType::Float,
+                ColumnType::Boolean,
+                ColumnType::Date,
+                ColumnType::Text,
+                ColumnType::Text,
+            ]
+        );
+        assert_eq!(
+            file.schema()[0],
+            ColumnSchema {
+                name: "id".to_owned(),
+                column_type: ColumnType::Integer,
+                nulls: 1,
+            }
+        );
+        assert_eq!(file.schema()[5].nulls, 3);
+        assert_eq!(file.column_type("joined"), Ok(ColumnType::Date));
+        assert_eq!(file.column_type(7), Err(CSVError::InvalidColIndex));
+
+        assert_eq!(file.matching_rows("joined < 2024-01-01"), Ok(vec![2]));
+        assert_eq!(file.matching_rows("active = no"), Ok(vec![2]));
+        assert_eq!(file.matching_rows("price >= 10"), Ok(vec![2, 3]));
+        assert_eq!(file.matching_rows("name > 9"), Ok(vec![1, 2]));
+    }
+
+    #[test]
+    fn test_group_by_aggregates() {
+        let file = load_temp(
+            "csvr_group_by.csv",
+            "region,sales,price,rep\nnorth,10,2.5,asha\nsouth,4,_,ravi\nnorth,7,3.5,_\nsouth,_,1,meena\n",
+        );
+        let aggregates =
+            Aggregate::parse_list("sum(sales), avg(price), count(*), count(rep), max(rep)")
+                .unwrap();
+        let groups = file
+            .group_by(&ColumnSelector::parse_list("region"), &aggregates)
+            .unwrap();
+        assert_eq!(
+            groups.header(),
+            [
+                "region",
+                "sum(sales)",
+                "avg(price)",
+                "count(*)",
+                "count(rep)",
+                "max(rep)"
+            ]
+        );
+        let rows: Vec<Vec<&str>> = groups
+            .rows()
+            .iter()
+            .map(|row| row.iter().map(Cell::as_str).collect())
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                ["north", "17", "3", "2", "1", "asha"],
+                ["south", "4", "1", "2", "2", "ravi"],
+            ]
+        );
+        assert_eq!(groups.file_path, "");
+
+        let total = file
+            .group_by(&[], &Aggregate::parse_list("min(price)").unwrap())
+            .unwrap();
+        assert_eq!(total.cell(1, 1), Some(&Cell::from("1")));
+
+        assert!(matches!(
+            file.group_by(
+                &["region".into()],
+                &Aggregate::parse_list("sum(rep)").unwrap()
+            ),
+            Err(CSVError::InvalidAggregate(_))
+        ));
+        assert_eq!(
+            Aggregate::parse_list("sum(*)"),
+            Err(CSVError::InvalidAggregate("sum(*)".to_owned()))
+        );
+        assert!(Aggregate::parse_list("median(sales)").is_err());
+    }
+
+    #[test]
+    fn test_group_by_into_file() {
+        let mut file = load_temp("csvr_group_into.csv", "city,qty\nPune,2\nGoa,1\nPune,3\n");
+        let target = env::temp_dir().join("csvr_group_into_result.csv");
+        query_resolver(
+            format!("group by city agg sum(qty) into {}", target.display()),
+            &mut file,
+        )
+        .unwrap();
+        assert_eq!(
+            fs::read_to_string(&target).unwrap(),
+            "city,sum(qty)\nPune,5\nGoa,1\n"
+        );
+        let _ = fs::remove_file(target);
+        assert_eq!(file.num_cols(), 2);
+        assert!(query_resolver("group city".to_owned(), &mut file).is_err());
+    }
+
+    #[test]
+    fn test_column_stats() {
+        let mut file = load_temp(
+            "csvr_stats.csv",
+            "city,price,joined\nPune,4,2024-03-01\nGoa,_,2023-12-31\nPune,10,\nDelhi,1,2024-01-15\nPune,5,_\n",
+        );
+        let price = file.column_stats("price").unwrap();
+        assert_eq!(price.column_type, ColumnType::Integer);
+        assert_eq!((price.count, price.nulls, price.distinct), (4, 1, 4));
+        assert_eq!(price.min.as_deref(), Some("1"));
+        assert_eq!(price.max.as_deref(), Some("10"));
+        assert_eq!(price.mean, Some(5.0));
+        assert_eq!(price.median, Some(4.5));
+        assert_eq!(price.std_dev, Some(14.0f64.sqrt()));
+
+        let city = file.column_stats(1).unwrap();
+        assert_eq!(city.distinct, 3);
+        assert_eq!(city.mean, None);
+        assert_eq!(
+            city.top,
+            [
+                ("Pune".to_owned(), 3),
+                ("Goa".to_owned(), 1),
+                ("Delhi".to_owned(), 1)
+            ]
+        );
+
+        let joined = &file.stats()[2];
+        assert_eq!(joined.nulls, 2);
+        assert_eq!(joined.min.as_deref(), Some("2023-12-31"));
+        assert_eq!(joined.max.as_deref(), Some("2024-03-01"));
+
+        assert!(file.column_stats(4).is_err());
+        query_resolver("stats col price".to_owned(), &mut file).unwrap();
+        query_resolver("stats file".to_owned(), &mut file).unwrap();
+        assert!(query_resolver("stats row 1".to_owned(), &mut file).is_err());
+    }
+
+    #[test]
+    fn test_join_kinds() {
+        let customers = load_temp(
+            "csvr_join_customers.csv",
+            "id,name,city\n1,asha,Pune\n2,ravi,Goa\n3,meena,_\n",
+        );
+        let mut orders = load_temp(
+            "csvr_join_orders.csv",
+            "order,customer_id,city\na,1,Delhi\nb,1,Pune\nc,4,Goa\nd,_,Goa\n",
+        );
+        orders.file_path = "orders.csv".to_owned();
+        let on = JoinKey::parse_list("id = customer_id").unwrap();
+
+        let inner = customers.join(&orders, JoinKind::Inner, &on).unwrap();
+        assert_eq!(
+            inner.header(),
+            ["id", "name", "city", "order", "orders.city"]
+        );
+        assert_eq!(
+            cells(&inner),
+            [
+                ["1", "asha", "Pune", "a", "Delhi"],
+                ["1", "asha", "Pune", "b", "Pune"]
+            ]
+        );
+        assert_eq!(inner.file_path, customers.file_path);
+
+        let left = customers.join(&orders, JoinKind::Left, &on).unwrap();
+        assert_eq!(left.num_rows(), 4);
+        assert_eq!(cells(&left)[2], ["2", "ravi", "Goa", "_", "_"]);
+
+        let right = customers.join(&orders, JoinKind::Right, &on).unwrap();
+        assert_eq!(
+            cells(&right)[2..],
+            [["4", "_", "_", "c", "Goa"], ["_", "_", "_", "d", "Goa"]]
+        );
+
+        let full = customers.join(&orders, JoinKind::Full, &on).unwrap();
+        assert_eq!(full.num_rows(), 6);
+
+        assert!(matches!(
+            customers.join(
+                &orders,
+                JoinKind::Inner,
+                &JoinKey::parse_list("id").unwrap()
+            ),
+            Err(CSVError::UnknownColumn(_))
+        ));
+        assert!(JoinKey::parse_list(" ").is_err());
+        assert!(JoinKey::parse_list("a = b = c").is_err());
+    }
+
+    #[test]
+    fn test_join_replaces_loaded_table() {
+        let mut file = load_temp("csvr_join_left.csv", "sku,qty\nx1,3\nx2,5\n");
+        let other = write_temp("csvr_join_right.csv", "sku,price\nx2,9.5\nx3,1\n");
+        query_resolver(format!("join full {} on sku", other.display()), &mut file).unwrap();
+        let _ = fs::remove_file(&other);
+        assert_eq!(file.header(), ["sku", "qty", "price"]);
+        assert_eq!(file.num_rows(), 3);
+        assert_eq!(file.cell(3, "sku"), Some(&Cell::from("x3")));
+        assert_eq!(file.max_col_lengths(), [3, 3, 5]);
+        assert!(query_resolver("join other.csv".to_owned(), &mut file).is_err());
+    }
+
+    #[test]
+    fn test_merge_files_by_name() {
+        let base = load_temp("csvr_merge_base.csv", "id,name,city\n1,asha,Pune\n");
+        let other = load_temp("csvr_merge_other.csv", "city,id,phone\nGoa,2,555\n");
+
+        let renamed = load_temp("csvr_merge_renamed.csv", "key,person,town\n2,ravi,Goa\n");
+        let mut file = base.clone();
+        assert!(file.merge_files(&renamed).is_ok());
+        assert_eq!(file.header(), ["id", "name", "city"]);
+        assert_eq!(cells(&file)[1], ["2", "ravi", "Goa"]);
+
+        let mut file = base.clone();
+        let options = MergeOptions {
+            by_name: true,
+            ..Default::default()
+        };
+        file.merge_files_with_options(&other, &options).unwrap();
+        assert_eq!(file.header(), ["id", "name", "city"]);
+        assert_eq!(cells(&file)[1], ["2", "_", "Goa"]);
+
+        let mut file = base.clone();
+        let options = MergeOptions {
+            by_name: true,
+            add_columns: true,
+            ..Default::default()
+        };
+        file.merge_files_with_options(&other, &options).unwrap();
+        assert_eq!(file.header(), ["id", "name", "city", "phone"]);
+        assert_eq!(
+            cells(&file),
+            [["1", "asha", "Pune", "_"], ["2", "_", "Goa", "555"]]
+        );
+        assert_eq!(file.max_col_lengths(), [2, 4, 4, 5]);
+
+        let narrow = load_temp("csvr_merge_narrow.csv", "id\n3\n");
+        let mut file = base.clone();
+        assert_eq!(
+            file.merge_files(&narrow).unwrap_err().to_string(),
+            CSVError::InvalidDimensions.to_string()
+        );
+        assert!(query_resolver("merge other.csv by title".to_owned(), &mut file).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_concat() {
+        let args = [
+            "csvr",
+            "--concat",
+            "a.csv",
+            "part-*.csv",
+            "--by-name",
+            "--source-column",
+            "file",
+        ]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+        let options = parse_args(args).unwrap();
+        assert_eq!(options.file_name, "a.csv");
+        assert_eq!(options.concat_files, ["part-*.csv"]);
+        let merge = options.merge_options();
+        assert!(merge.by_name && !merge.add_columns);
+        assert_eq!(merge.source_column.as_deref(), Some("file"));
+
+        let args = ["csvr", "--concat", "a.csv", "b.csv", "--add-columns"];
+        let options = parse_args(args.into_iter().map(str::to_owned).collect()).unwrap();
+        let merge = options.merge_options();
+        assert!(merge.by_name && merge.add_columns);
+
+        let args = vec!["csvr".to_owned(), "a.csv".to_owned(), "b.csv".to_owned()];
+        assert_eq!(parse_args(args), Err(FileLoadError::ExtraArguments));
+        assert_eq!(
+            expand_paths("/nonexistent-csvr-dir/*.csv"),
+            Err(FileLoadError::NoMatchingFiles(
+                "/nonexistent-csvr-dir/*.csv".to_owned()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_merge_glob_with_source_column() {
+        let dir = env::temp_dir().join("csvr_merge_glob");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("part-1.csv"), "id,qty\n2,5\n").unwrap();
+        fs::write(dir.join("part-2.csv"), "qty,id\n7,3\n").unwrap();
+        fs::write(dir.join("other.csv"), "id,qty\n9,9\n").unwrap();
+        let base = dir.join("base.csv");
+        fs::write(&base, "id,qty\n1,4\n").unwrap();
+        fs::write(dir.join("bad-1.csv"), "id,qty\n5,5\n").unwrap();
+        fs::write(dir.join("bad-2.csv"), "id\n6\n").unwrap();
+        let mut file = csvr::CSVFile::open(&base.to_string_lossy()).unwrap();
+
+        let pattern = dir.join("bad-*.csv");
+        assert!(query_resolver(format!("merge \"{}\"", pattern.display()), &mut file).is_err());
+        assert_eq!(file.num_rows(), 1);
+        assert!(file.history().is_empty());
+
+        let pattern = dir.join("part-*.csv");
+        query_resolver(
+            format!("merge \"{}\" by name source file", pattern.display()),
+            &mut file,
+        )
+        .unwrap();
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(file.history().len(), 1);
+        let mut undone = file.clone();
+        undone.undo().unwrap();
+        assert_eq!(undone.header(), ["id", "qty"]);
+        assert_eq!(undone.num_rows(), 1);
+        assert_eq!(file.header(), ["id", "qty", "file"]);
+        let sources: Vec<String> = file.rows().iter().map(|row| row[2].to_string()).collect();
+        assert_eq!(
+            sources,
+            [
+                base.to_string_lossy().into_owned(),
+                dir.join("part-1.csv").to_string_lossy().into_owned(),
+                dir.join("part-2.csv").to_string_lossy().into_owned(),
+            ]
+        );
+        assert_eq!(file.cell(3, "id"), Some(&Cell::from("3")));
+
+        let other = table(1, 2);
+        let options = MergeOptions {
+            source_column: Some("file".to_owned()),
+            ..Default::default()
+        };
+        file.merge_files_with_options(&other, &options).unwrap();
+        assert_eq!(file.cell(4, 1), Some(&Cell::from("11")));
+        assert_eq!(file.cell(4, 3), Some(&Cell::from("")));
+        assert!(query_resolver("merge part-1.csv source".to_owned(), &mut file).is_err());
+    }
+
+    #[test]
+    fn test_undo_redo_column_edits() {
+        let mut file = table(3, 3);
+        let original = file.clone();
+        query_resolver("delete col col2".to_owned(), &mut file).unwrap();
+        query_resolver("modify col 1 a,bbbbbb,c".to_owned(), &mut file).unwrap();
+        query_resolver("sort by col1 desc apply".to_owned(), &mut file).unwrap();
+        file.set_cell(1, 2, "x").unwrap();
+        assert_eq!(
+            file.history(),
+            [
+                "delete col col2",
+                "modify col col1",
+                "sort by col1 desc",
+                "set item 1 2"
+            ]
+        );
+        assert_eq!(file.max_col_lengths(), [6, 4]);
+
+        for _ in 0..4 {
+            query_resolver("undo".to_owned(), &mut file).unwrap();
+        }
+        assert_eq!(file, original);
+        assert_eq!(file.max_col_lengths(), original.max_col_lengths());
+        assert_eq!(
+            query_resolver("undo".to_owned(), &mut file),
+            Err(CSVError::NothingToUndo.to_string())
+        );
+
+        query_resolver("redo".to_owned(), &mut file).unwrap();
+        query_resolver("redo".to_owned(), &mut file).unwrap();
+        assert_eq!(file.header(), ["col1", "col3"]);
+        assert_eq!(file.cell(2, 1), Some(&Cell::from("bbbbbb")));
+        assert_eq!(file.redo_history(), ["sort by col1 desc", "set item 1 2"]);
+
+        file.delete_row(1).unwrap();
+        assert!(file.redo_history().is_empty());
+        assert_eq!(
+            file.redo().unwrap_err().downcast_ref::<CSVError>(),
+            Some(&CSVError::NothingToRedo)
+        );
+        assert!(query_resolver("history all".to_owned(), &mut file).is_err());
+    }
+
+    #[test]
+    fn test_undo_merge_and_join() {
+        let mut file = load_temp("csvr_undo_left.csv", "sku,qty\nx1,3\nx2,5\n");
+        let original = file.clone();
+        let other = load_temp("csvr_undo_right.csv", "sku,price\nx2,9.5\nx3,1\n");
+        let options = MergeOptions {
+            by_name: true,
+            add_columns: true,
+            source_column: Some("file".to_owned()),
+        };
+        file.merge_files_with_options(&other, &options).unwrap();
+        file.join_files(&other, JoinKind::Left, &JoinKey::parse_list("sku").unwrap())
+            .unwrap();
+        assert_eq!(
+            file.header(),
+            ["sku", "qty", "file", "price", "csvr_undo_right.price"]
+        );
+
+        file.undo().unwrap();
+        assert_eq!(file.header(), ["sku", "qty", "file", "price"]);
+        assert_eq!(file.num_rows(), 4);
+        file.undo().unwrap();
+        assert_eq!(file, original);
+        assert_eq!(file.max_col_lengths(), original.max_col_lengths());
+
+        file.redo().unwrap();
+        assert_eq!(file.cell(4, "price"), Some(&Cell::from("1")));
+        file.clear_history();
+        assert!(file.history().is_empty() && file.redo_history().is_empty());
+    }
+
+    #[test]
+    fn test_modified_until_written() {
+        let mut file = table(2, 2);
+        assert!(!file.is_modified());
+        file.set_confirm(AlwaysNo);
+        file.delete_row(1).unwrap();
+        query_resolver("sort by col1".to_owned(), &mut file).unwrap();
+        assert!(!file.is_modified());
+
+        file.set_confirm(AlwaysYes);
+        file.delete_row(1).unwrap();
+        assert!(file.is_modified());
+        let target = env::temp_dir().join("csvr_modified_target.csv");
+        query_resolver(format!("write {}", target.display()), &mut file).unwrap();
+        assert!(file.is_modified());
+        assert_ne!(file.file_path, target.to_string_lossy());
+
+        file.file_path = target.to_string_lossy().into_owned();
+        query_resolver(format!("write {}", target.display()), &mut file).unwrap();
+        assert!(!file.is_modified());
+        file.delete_row(1).unwrap();
+        query_resolver("write".to_owned(), &mut file).unwrap();
+        let _ = fs::remove_file(&target);
+        assert!(!file.is_modified());
+
+        file.undo().unwrap();
+        assert!(file.is_modified());
+        file.file_path = "/nonexistent-csvr-dir/file.csv".to_owned();
+        assert!(matches!(
+            file.write_to_file(),
+            Err(CSVError::WriteFailed(_))
+        ));
+        assert!(file.is_modified());
+    }
+
+    #[test]
+    fn test_write_keeps_backups() {
+        let dir = env::temp_dir().join("csvr_write_backups");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("data.csv");
+        fs::write(&path, "a,b\n1,2").unwrap();
+        let mut file = csvr::CSVFile::open(&path.to_string_lossy()).unwrap();
+
+        file.set_cell(1, 1, "x").unwrap();
+        file.backup = Backup::Bak;
+        file.write_to_file().unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "a,b\nx,2");
+        assert_eq!(
+            fs::read_to_string(dir.join("data.csv.bak")).unwrap(),
+            "a,b\n1,2"
+        );
+
+        file.set_cell(1, 1, "y").unwrap();
+        file.backup = Backup::Timestamped;
+        query_resolver("write".to_owned(), &mut file).unwrap();
+        let names: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        let stamped = names
+            .iter()
+            .find(|name| name.len() == "data.csv.20240131-093000.bak".len())
+            .unwrap();
+        assert_eq!(fs::read_to_string(dir.join(stamped)).unwrap(), "a,b\nx,2");
+        assert_eq!(names.len(), 3);
+
+        file.file_path = dir.join("missing/data.csv").to_string_lossy().into_owned();
+        assert!(query_resolver("write".to_owned(), &mut file).is_err());
+        file.file_path = dir.join("data").to_string_lossy().into_owned();
+        assert_eq!(file.write_to_file(), Err(CSVError::MissingExtension));
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 3);
+        let _ = fs::remove_dir_all(&dir);
+
+        let args = vec!["csvr", "a.csv", "--backup", "timestamp"];
+        let args = args.into_iter().map(str::to_owned).collect();
+        assert_eq!(parse_args(args).unwrap().backup, Backup::Timestamped);
+        let args = vec!["csvr", "a.csv", "--backup", "all"];
+        let args = args.into_iter().map(str::to_owned).collect();
+        assert_eq!(
+            parse_args(args),
+            Err(FileLoadError::InvalidBackup("all".to_owned()))
+        );
+    }
+
+    #[test]
+    fn test_write_preserves_framing() {
+        let content = "\u{feff}id,name\r\n1,asha\r\n";
+        let source = write_temp("csvr_framing_source.csv", content);
+        let mut file = csvr::CSVFile::open(&source.to_string_lossy()).unwrap();
+        let _ = fs::remove_file(&source);
+        assert_eq!(file.header(), ["id", "name"]);
+        assert_eq!(file.num_rows(), 1);
+        assert_eq!(file.cell(1, "name"), Some(&Cell::from("asha")));
+        assert_eq!(
+            file.framing,
+            Framing {
+                bom: true,
+                trailing_newline: true
+            }
+        );
+
+        let mut out = Vec::new();
+        file.write_to(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), content);
+
+        file.normalize();
+        let mut out = Vec::new();
+        file.write_to(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "id,name\n1,asha\n");
+
+        let bare = csvr::CSVFile::from_reader("a;b\r\n1;2".as_bytes(), &Default::default());
+        let bare = bare.unwrap();
+        assert_eq!(bare.framing, Framing::default());
+        assert_eq!(bare.dialect.line_terminator, LineTerminator::CrLf);
+        let mut out = Vec::new();
+        bare.write_to(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "a;b\r\n1;2");
+
+        let args = ["csvr", "a.csv", "--normalize"];
+        let args = args.into_iter().map(str::to_owned).collect();
+        assert!(parse_args(args).unwrap().normalize);
+    }
+
+    #[test]
+    fn test_legacy_and_utf16_encodings() {
+        let load = |bytes: &[u8]| -> csvr::CSVFile {
+            csvr::CSVFile::from_reader(bytes, &Default::default()).unwrap()
+        };
+        let written = |file: &csvr::CSVFile| -> Vec<u8> {
+            let mut out = Vec::new();
+            file.write_to(&mut out).unwrap();
+            out
+        };
+
+        let cp1252 = b"name;price\r\ncaf\xe9;\x80 5\r\n";
+        let mut file = load(cp1252);
+        assert_eq!(file.encoding, TextEncoding::Windows1252);
+        assert_eq!(file.cell(1, "name"), Some(&Cell::from("café")));
+        assert_eq!(file.cell(1, "price"), Some(&Cell::from("€ 5")));
+        assert_eq!(written(&file), cp1252);
+        file.set_cell(1, 1, "naïve ☕").unwrap();
+        let err = file.write_to(&mut Vec::new()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            CSVError::Unencodable("'☕' in Windows-1252".to_owned()).to_string()
+        );
+        file.encoding = TextEncoding::Utf8;
+        assert_eq!(written(&file), "name;price\r\nnaïve ☕;€ 5\r\n".as_bytes());
+
+        let utf16: Vec<u8> = "\u{feff}id,name\n1,Zoë\n"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        let mut file = load(&utf16);
+        assert_eq!(file.encoding, TextEncoding::Utf16Le);
+        assert_eq!(file.header(), ["id", "name"]);
+        assert!(file.framing.bom);
+        assert_eq!(written(&file), utf16);
+        file.encoding = TextEncoding::Latin1;
+        assert_eq!(written(&file), b"id,name\n1,Zo\xeb\n");
+
+        let no_bom: Vec<u8> = "a,b\n1,2\n"
+            .encode_utf16()
+            .flat_map(u16::to_be_bytes)
+            .collect();
+        assert_eq!(TextEncoding::detect(&no_bom), TextEncoding::Utf16Be);
+        assert_eq!(load(&no_bom).cell(1, "b"), Some(&Cell::from("2")));
+
+        let wide = "名前,都市\n東京,Ā\nĀdam,Łódź\n";
+        let little: Vec<u8> = wide.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let big: Vec<u8> = wide.encode_utf16().flat_map(u16::to_be_bytes).collect();
+        assert_eq!(TextEncoding::detect(&little), TextEncoding::Utf16Le);
+        assert_eq!(TextEncoding::detect(&big), TextEncoding::Utf16Be);
+        assert_eq!(load(&big).cell(2, "都市"), Some(&Cell::from("Łódź")));
+
+        let args = [
+            "csvr",
+            "a.csv",
+            "--encoding",
+            "CP1252",
+            "--write-encoding",
+            "utf-8",
+        ];
+        let options = parse_args(args.into_iter().map(str::to_owned).collect()).unwrap();
+        assert_eq!(options.encoding, Some(TextEncoding::Windows1252));
+        assert_eq!(options.write_encoding, Some(TextEncoding::Utf8));
+        let args = ["csvr", "a.csv", "--encoding", "ebcdic"];
+        assert_eq!(
+            parse_args(args.into_iter().map(str::to_owned).collect()),
+            Err(FileLoadError::UnknownEncoding("ebcdic".to_owned()))
+        );
+    }
+
+    #[test]
+    fn test_headerless_files() {
+        let options = csvr::LoadOptions {
+            headerless: true,
+            ..Default::default()
+        };
+        let input = "1,asha,Pune\n2,ravi,Delhi\n3,meena,Goa\n";
+        let mut file = csvr::CSVFile::from_reader(input.as_bytes(), &options).unwrap();
+        assert!(file.headerless);
+        assert_eq!(file.header(), ["col1", "col2", "col3"]);
+        assert_eq!(file.num_rows(), 3);
+        assert_eq!(file.cell(1, "col2"), Some(&Cell::from("asha")));
+
+        query_resolver("delete where col1 = 2".to_owned(), &mut file).unwrap();
+        let mut out = Vec::new();
+        file.write_to(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "1,asha,Pune\n3,meena,Goa\n"
+        );
+
+        file.headerless = false;
+        let mut out = Vec::new();
+        file.write_to(&mut out).unwrap();
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .starts_with("col1,col2,col3\n1,asha,Pune\n"));
+
+        let other = write_temp("csvr_headerless_other.csv", "3,c\n4,d\n");
+        let mut file = csvr::CSVFile::from_reader("1,a\n2,b\n".as_bytes(), &options).unwrap();
+        let merged = query_resolver(format!("merge \"{}\"", other.display()), &mut file);
+        let _ = fs::remove_file(&other);
+        merged.unwrap();
+        assert_eq!(file.num_rows(), 4);
+        assert_eq!(file.cell(3, "col2"), Some(&Cell::from("c")));
+
+        let args = ["csvr", "a.csv", "--no-header", "--write-header"];
+        let options = parse_args(args.into_iter().map(str::to_owned).collect()).unwrap();
+        assert!(options.no_header && options.write_header);
+    }
 }
 
 // Red: \x1b[31m
@@ -0,0 +1,90 @@
+/// Parses an entry as a number, ignoring surrounding whitespace.
+pub(crate) fn parse_number(text: &str) -> Option<f64> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    text.parse::<f64>().ok().filter(|number| number.is_finite())
+}
+
+/// Parses an entry as a whole number, ignoring surrounding whitespace.
+pub(crate) fn parse_integer(text: &str) -> Option<i64> {
+    let text = text.trim();
+    text.strip_prefix('+').unwrap_or(text).parse::<i64>().ok()
+}
+
+/// Parses an entry as a boolean: `true`/`false` or `yes`/`no`, in any case.
+pub(crate) fn parse_bool(text: &str) -> Option<bool> {
+    match text.trim().to_ascii_lowercase().as_str() {
+        "true" | "yes" => Some(true),
+        "false" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// A calendar date with an optional time of day, ordered chronologically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct DateTime {
+    year: i32,
+    month: u32,
+    day: u32,
+    seconds: u32,
+}
+
+/// Parses an entry as a date in `YYYY-MM-DD` or `YYYY/MM/DD` form, optionally followed by a
+/// time of day (`HH:MM` or `HH:MM:SS`) separated by `T` or a space.
+pub(crate) fn parse_date(text: &str) -> Option<DateTime> {
+    let text = text.trim();
+    let (date, time) = match text.find(['T', ' ']) {
+        Some(index) => (&text[..index], Some(text[index + 1..].trim())),
+        None => (text, None),
+    };
+
+    let separator = if date.contains('-') { '-' } else { '/' };
+    let mut parts = date.split(separator);
+    let (year, month, day) = (parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() || year.len() != 4 || month.len() > 2 || day.len() > 2 {
+        return None;
+    }
+    let year = year.parse::<i32>().ok()?;
+    let month = month.parse::<u32>().ok()?;
+    let day = day.parse::<u32>().ok()?;
+    if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+        return None;
+    }
+
+    let seconds = match time {
+        Some(time) => parse_time(time)?,
+        None => 0,
+    };
+    Some(DateTime {
+        year,
+        month,
+        day,
+        seconds,
+    })
+}
+
+fn parse_time(time: &str) -> Option<u32> {
+    let time = time.trim_end_matches('Z');
+    let mut parts = time.split(':');
+    let hours = parts.next()?.parse::<u32>().ok()?;
+    let minutes = parts.next()?.parse::<u32>().ok()?;
+    let seconds = match parts.next() {
+        Some(seconds) => seconds.split('.').next()?.parse::<u32>().ok()?,
+        None => 0,
+    };
+    if parts.next().is_some() || hours > 23 || minutes > 59 || seconds > 59 {
+        return None;
+    }
+    Some(hours * 3600 + minutes * 60 + seconds)
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
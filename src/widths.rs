@@ -0,0 +1,87 @@
+use std::collections::BTreeMap;
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::Cell;
+
+/// Returns the number of terminal columns taken by `text`, counting wide characters (CJK,
+/// emoji) as two columns and combining marks as none.
+pub fn display_width(text: &str) -> usize {
+    UnicodeWidthStr::width(text)
+}
+
+/// Tracks the display width of every column of a [`CSVFile`](crate::CSVFile).
+///
+/// Each column keeps a count of how many of its entries (header included) have each width, so
+/// that the widest entry is known after any change without rescanning the column.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct ColumnWidths {
+    columns: Vec<BTreeMap<usize, usize>>,
+}
+
+impl ColumnWidths {
+    pub(crate) fn new(header: &[String], rows: &[Vec<Cell>]) -> Self {
+        let mut widths = ColumnWidths {
+            columns: header
+                .iter()
+                .map(|title| BTreeMap::from([(display_width(title), 1)]))
+                .collect(),
+        };
+        for row in rows {
+            widths.add_row(row);
+        }
+        widths
+    }
+
+    /// Returns the width of the widest entry of each column.
+    pub(crate) fn max(&self) -> Vec<usize> {
+        self.columns
+            .iter()
+            .map(|counts| counts.keys().next_back().copied().unwrap_or(0))
+            .collect()
+    }
+
+    pub(crate) fn add_row(&mut self, row: &[Cell]) {
+        for (counts, cell) in self.columns.iter_mut().zip(row) {
+            add(counts, cell.as_str());
+        }
+    }
+
+    pub(crate) fn remove_row(&mut self, row: &[Cell]) {
+        for (counts, cell) in self.columns.iter_mut().zip(row) {
+            remove(counts, cell.as_str());
+        }
+    }
+
+    pub(crate) fn replace(&mut self, col: usize, old: &str, new: &str) {
+        remove(&mut self.columns[col], old);
+        add(&mut self.columns[col], new);
+    }
+
+    pub(crate) fn insert_column(&mut self, col: usize, title: &str, cells: &[Cell]) {
+        let mut counts = BTreeMap::new();
+        add(&mut counts, title);
+        for cell in cells {
+            add(&mut counts, cell.as_str());
+        }
+        self.columns.insert(col, counts);
+    }
+
+    pub(crate) fn remove_column(&mut self, col: usize) {
+        self.columns.remove(col);
+    }
+}
+
+fn add(counts: &mut BTreeMap<usize, usize>, text: &str) {
+    *counts.entry(display_width(text)).or_insert(0) += 1;
+}
+
+fn remove(counts: &mut BTreeMap<usize, usize>, text: &str) {
+    let width = display_width(text);
+    if let Some(count) = counts.get_mut(&width) {
+        *count -= 1;
+        if *count == 0 {
+            counts.remove(&width);
+        }
+    }
+}